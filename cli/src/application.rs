@@ -4,8 +4,9 @@ use super::{commands::CliCommand, config::CliConfig};
 use abscissa_core::{
     Application, FrameworkError, StandardPaths,
     application::{AppCell, State},
+    command::Verbosity,
     config::{self, CfgCell},
-    trace,
+    terminal::ColorChoice,
 };
 
 /// Application state
@@ -47,11 +48,11 @@ impl Application for CliApplication {
         Ok(())
     }
 
-    fn tracing_config(&self, command: &CliCommand) -> trace::Config {
-        if command.verbose {
-            trace::Config::verbose()
-        } else {
-            trace::Config::default()
-        }
+    fn term_colors(&self, command: &CliCommand) -> ColorChoice {
+        command.color.into()
+    }
+
+    fn verbosity(&self, command: &CliCommand) -> Verbosity {
+        command.verbosity
     }
 }
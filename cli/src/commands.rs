@@ -5,7 +5,9 @@ pub mod new;
 
 use self::{generate::GenCommand, new::NewCommand};
 use super::config::CliConfig;
-use abscissa_core::{Command, Configurable, Runnable, clap::Parser};
+use abscissa_core::{
+    Command, Configurable, Runnable, clap::Parser, command::Verbosity, terminal::ColorMode,
+};
 use std::path::PathBuf;
 
 #[derive(Debug, Parser, Runnable)]
@@ -24,9 +26,13 @@ pub struct CliCommand {
     #[command(subcommand)]
     subcmd: SubCommands,
 
-    /// Enable verbose mode
-    #[arg(short, long)]
-    pub verbose: bool,
+    /// Verbosity of log and status output
+    #[command(flatten)]
+    pub verbosity: Verbosity,
+
+    /// When to use colors in output
+    #[arg(long, global = true, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorMode,
 }
 
 impl Runnable for CliCommand {
@@ -10,16 +10,32 @@ pub use self::{cell::AppCell, exit::fatal_error, name::Name, state::State};
 use crate::{
     FrameworkError,
     FrameworkErrorKind::*,
-    command::Command,
-    component::Component,
+    command::{Command, Verbosity},
+    component::{Component, graph, timing},
     config::{self, Config, Configurable},
     path::{AbsPathBuf, ExePath, RootPath},
     runnable::Runnable,
     shutdown::Shutdown,
-    terminal::{ColorChoice, component::Terminal},
+    terminal::{self, ColorChoice, component::Terminal},
+    thread,
     trace::{self, Tracing},
 };
-use std::{env, ffi::OsString, path::Path, process, vec};
+use std::{
+    env,
+    ffi::OsString,
+    io::Write,
+    path::Path,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+    vec,
+};
+
+/// Default amount of time to wait for managed threads to exit on shutdown.
+pub const DEFAULT_THREAD_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval at which to retry locking the thread manager when joining threads.
+const THREAD_LOCK_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Application types implementing this trait own global application state,
 /// including configuration and arbitrary other values stored within
@@ -44,8 +60,13 @@ pub trait Application: Default + Sized + 'static {
 
     /// Run application with the given command-line arguments and running the
     /// appropriate `Command` type.
+    ///
+    /// If a critical thread (or one with the `Escalate` supervision policy)
+    /// fails, the application is shut down with `Shutdown::Crash` by the
+    /// failing thread, even while the command is still running.
     fn run<I, T>(app_cell: &'static AppCell<Self>, args: I)
     where
+        Self: Send + Sync,
         I: IntoIterator<Item = T>,
         T: Into<OsString> + Clone,
     {
@@ -54,6 +75,11 @@ pub trait Application: Default + Sized + 'static {
 
         // Initialize application
         let mut app = Self::default();
+        app.state()
+            .threads_mut()
+            .set_escalation_handler(move |name, failure| {
+                crash_on_thread_failure(app_cell, name, failure)
+            });
         app.init(&command).unwrap_or_else(|e| fatal_error(&app, &e));
         app_cell.set_once(app);
 
@@ -87,11 +113,29 @@ pub trait Application: Default + Sized + 'static {
 
     /// Load this application's configuration and initialize its components.
     fn init(&mut self, command: &Self::Cmd) -> Result<(), FrameworkError> {
+        // Load the raw sections of the configuration file, which determine
+        // which components are enabled and are delivered to components which
+        // declare their own configuration section
+        if let Some(path) = command.config_path() {
+            let sections = self.load_config_sections(&path)?;
+            self.state()
+                .components_mut()
+                .set_config_sections(sections)?;
+        }
+
         // Create and register components with the application.
-        // We do this first to calculate a proper dependency ordering before
-        // application configuration is processed
+        // We do this before loading the application's configuration to
+        // calculate a proper dependency ordering before it is processed
         self.register_components(command)?;
 
+        // Print the component dependency graph (if requested) before the
+        // application's configuration is loaded, so it works regardless
+        if let Some(format) = self.component_graph(command) {
+            let graph = self.state().components().dependency_graph();
+            write!(terminal::stdout().lock(), "{}", graph.render(format))?;
+            self.shutdown(Shutdown::Graceful);
+        }
+
         // Load configuration
         let config = command
             .config_path()
@@ -103,6 +147,10 @@ pub trait Application: Default + Sized + 'static {
         // in order to signal state in the application lifecycle
         self.after_config(command.process_config(config)?)?;
 
+        if timing::enabled() {
+            self.state().components().timings().startup().print()?;
+        }
+
         Ok(())
     }
 
@@ -112,24 +160,46 @@ pub trait Application: Default + Sized + 'static {
         &mut self,
         command: &Self::Cmd,
     ) -> Result<Vec<Box<dyn Component<Self>>>, FrameworkError> {
-        let terminal = Terminal::new(self.term_colors(command));
-        let tracing = Tracing::new(self.tracing_config(command), self.term_colors(command))
-            .expect("tracing subsystem failed to initialize");
+        terminal::status::set_verbosity(self.verbosity(command).level());
 
-        Ok(vec![Box::new(terminal), Box::new(tracing)])
+        let components = self.state().components();
+        let terminal = components.construct(|| Ok(Terminal::new(self.term_colors(command))))?;
+        let tracing = components.construct(|| {
+            Tracing::with_overrides(
+                self.tracing_config(command),
+                self.tracing_overrides(command),
+                self.term_colors(command),
+            )
+        })?;
+
+        Ok(vec![terminal, tracing])
     }
 
     /// Load configuration from the given path.
     ///
+    /// Sections reserved by the framework (e.g. `[components]`) are excluded
+    /// (see [`config::Sections::application_config`]).
+    ///
     /// Returns an error if the configuration could not be loaded.
     fn load_config(&mut self, path: &Path) -> Result<Self::Cfg, FrameworkError> {
+        self.load_config_sections(path)?.application_config()
+    }
+
+    /// Load the raw sections of the configuration file at the given path.
+    ///
+    /// This is used by `init` to determine which components are enabled and
+    /// to configure components which declare their own section, before the
+    /// application's configuration is loaded with `load_config`.
+    ///
+    /// Returns an error if the configuration could not be loaded.
+    fn load_config_sections(&mut self, path: &Path) -> Result<config::Sections, FrameworkError> {
         let canonical_path = AbsPathBuf::canonicalize(path).map_err(|e| {
             let path_error = PathError {
                 name: Some(path.into()),
             };
             FrameworkError::from(ConfigError.context(path_error))
         })?;
-        Self::Cfg::load_toml_file(canonical_path)
+        config::Sections::load_toml_file(canonical_path)
     }
 
     /// Name of this application as a string.
@@ -152,37 +222,82 @@ pub trait Application: Default + Sized + 'static {
         ColorChoice::Auto
     }
 
-    /// Get the tracing configuration for this application.
+    /// Verbosity of this application's status and log output.
+    fn verbosity(&self, command: &Self::Cmd) -> Verbosity {
+        Verbosity::default()
+    }
+
+    /// Format in which to print the component dependency graph, if it was
+    /// requested by command-line options.
+    ///
+    /// If so, the graph is printed once components are registered (with the
+    /// `[components]` section of the configuration file applied), and the
+    /// application exits without loading the rest of its configuration or
+    /// running the command.
+    fn component_graph(&self, command: &Self::Cmd) -> Option<graph::Format> {
+        None
+    }
+
+    /// Get the tracing configuration for this application, used until the
+    /// `[tracing]` section of the configuration file (if any) is loaded.
     fn tracing_config(&self, command: &Self::Cmd) -> trace::Config {
-        trace::Config::default()
+        self.tracing_overrides(command)
+            .apply(trace::Config::default())
+    }
+
+    /// Get the tracing options given on the command line, which take
+    /// precedence over the `[tracing]` section of the configuration file.
+    fn tracing_overrides(&self, command: &Self::Cmd) -> trace::Overrides {
+        self.verbosity(command).tracing_overrides()
+    }
+
+    /// Maximum amount of time to wait for the threads spawned by the
+    /// application's thread manager to exit when shutting down.
+    fn thread_shutdown_timeout(&self, shutdown: Shutdown) -> Duration {
+        DEFAULT_THREAD_SHUTDOWN_TIMEOUT
     }
 
     /// Shut down this application gracefully, exiting with success.
     fn shutdown(&self, shutdown: Shutdown) -> ! {
-        let components = self.state().components();
-
-        if let Err(e) = components.shutdown(self, shutdown) {
+        if let Err(e) = self.state().components().shutdown(self, shutdown) {
             fatal_error(self, &e)
         }
 
+        // Join threads after notifying components, which may shut down the
+        // threads they own (e.g. worker pools) according to the shutdown type
+        join_threads(self, shutdown);
+
+        if timing::enabled() {
+            let _ = self.state().components().timings().shutdown().print();
+        }
+
         process::exit(0);
     }
 
     /// Shut down this application gracefully, exiting with user-defined exit code.
     fn shutdown_with_exitcode(&self, shutdown: Shutdown, exit_code: i32) -> ! {
-        let components = self.state().components();
-
-        if let Err(e) = components.shutdown(self, shutdown) {
+        if let Err(e) = self.state().components().shutdown(self, shutdown) {
             fatal_error(self, &e)
         }
 
+        // Join threads after notifying components, which may shut down the
+        // threads they own (e.g. worker pools) according to the shutdown type
+        join_threads(self, shutdown);
+
+        if timing::enabled() {
+            let _ = self.state().components().timings().shutdown().print();
+        }
+
         process::exit(exit_code);
     }
 }
 
 /// Boot the given application, parsing subcommand and options from
 /// command-line arguments, and terminating when complete.
-pub fn boot<A: Application>(app_cell: &'static AppCell<A>) -> ! {
+pub fn boot<A>(app_cell: &'static AppCell<A>) -> !
+where
+    A: Application + Send + Sync,
+{
     let args = env::args_os();
     boot_with_args(app_cell, args)
 }
@@ -194,10 +309,64 @@ pub fn boot<A: Application>(app_cell: &'static AppCell<A>) -> ! {
 /// e.g. perform glob expansion on Windows. Otherwise use [boot].
 pub fn boot_with_args<A, I, T>(app_cell: &'static AppCell<A>, args: I) -> !
 where
-    A: Application,
+    A: Application + Send + Sync,
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
 {
     A::run(app_cell, args);
     process::exit(0);
 }
+
+/// Signal the application's managed threads to terminate and wait for them
+/// to exit, logging any which panicked or failed to exit in time.
+fn join_threads<A: Application>(app: &A, shutdown: Shutdown) {
+    let deadline = Instant::now() + app.thread_shutdown_timeout(shutdown);
+
+    // Don't wait for the thread manager indefinitely: when a failing thread
+    // shuts down the application, another thread may hold it while waiting
+    // for the failing thread to finish
+    let mut threads = loop {
+        if let Some(threads) = app.state().try_threads_mut() {
+            break threads;
+        }
+
+        if Instant::now() >= deadline {
+            error!("couldn't lock the thread manager to join threads");
+            return;
+        }
+
+        std::thread::sleep(THREAD_LOCK_POLL_INTERVAL);
+    };
+
+    let timeout = deadline.saturating_duration_since(Instant::now());
+
+    if let Err(e) = threads.join_timeout(timeout) {
+        error!("{}", e);
+    }
+}
+
+/// Crash the application from a critical thread (or one with the `Escalate`
+/// supervision policy) which failed.
+///
+/// Only the first failure shuts down the application: any which occur while
+/// it's shutting down are just reported.
+fn crash_on_thread_failure<A>(
+    app_cell: &'static AppCell<A>,
+    name: &thread::Name,
+    failure: &thread::Failure,
+) where
+    A: Application + Send + Sync,
+{
+    static CRASHING: AtomicBool = AtomicBool::new(false);
+
+    status_err!("thread '{}' failed: {}", name, failure);
+
+    if CRASHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    match app_cell.get() {
+        Some(app) => app.shutdown_with_exitcode(Shutdown::Crash, 1),
+        None => process::exit(1),
+    }
+}
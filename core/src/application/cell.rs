@@ -24,6 +24,11 @@ impl<A> AppCell<A>
 where
     A: Application,
 {
+    /// Get the application state, if it has been initialized.
+    pub(crate) fn get(&self) -> Option<&A> {
+        self.0.get()
+    }
+
     /// Set the application state to the given value.
     ///
     /// This can only be performed once without causing a crash.
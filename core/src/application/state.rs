@@ -1,17 +1,22 @@
 //! Application state managed by the framework.
 
-use crate::{application::Application, component, thread};
-use std::sync::RwLock;
+use crate::{
+    FrameworkError, application::Application, component, component::Component, event, thread,
+};
+use std::sync::{Arc, RwLock, TryLockError};
 
 /// Error message to use for mutex error panics.
 const MUTEX_ERR_MSG: &str = "error acquiring mutex";
 
 /// Framework-managed application state
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct State<A: Application + 'static> {
     /// Application components.
     components: RwLock<component::Registry<A>>,
 
+    /// Event bus.
+    events: Arc<event::Bus>,
+
     /// Application paths.
     paths: A::Paths,
 
@@ -33,6 +38,58 @@ where
         self.components.write().expect(MUTEX_ERR_MSG)
     }
 
+    /// Obtain a typed reference to the component of type `C`.
+    ///
+    /// Only the component itself remains locked: the lock on the component
+    /// registry is released before returning.
+    pub fn component<C>(&self) -> Result<component::Ref<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.components().component()
+    }
+
+    /// Obtain a typed mutable reference to the component of type `C`.
+    ///
+    /// Only the component itself remains locked: the lock on the component
+    /// registry is released before returning.
+    pub fn component_mut<C>(&self) -> Result<component::RefMut<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.components().component_mut()
+    }
+
+    /// Resolve a typed component handle to a reference to its component.
+    pub fn resolve<C>(
+        &self,
+        handle: component::Handle<C>,
+    ) -> Result<component::Ref<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.components().resolve(handle)
+    }
+
+    /// Resolve a typed component handle to a mutable reference to its
+    /// component.
+    pub fn resolve_mut<C>(
+        &self,
+        handle: component::Handle<C>,
+    ) -> Result<component::RefMut<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.components().resolve_mut(handle)
+    }
+
+    /// Borrow the event bus.
+    ///
+    /// Publishing events does not require a lock on the component registry.
+    pub fn events(&self) -> &Arc<event::Bus> {
+        &self.events
+    }
+
     /// Borrow the application paths.
     pub fn paths(&self) -> &A::Paths {
         &self.paths
@@ -47,4 +104,30 @@ where
     pub fn threads_mut(&self) -> thread::manager::Writer<'_> {
         self.threads.write().expect(MUTEX_ERR_MSG)
     }
+
+    /// Obtain a mutable lock on the thread manager, unless it's currently
+    /// locked.
+    pub(crate) fn try_threads_mut(&self) -> Option<thread::manager::Writer<'_>> {
+        match self.threads.try_write() {
+            Ok(threads) => Some(threads),
+            Err(TryLockError::WouldBlock) => None,
+            Err(TryLockError::Poisoned(_)) => panic!("{}", MUTEX_ERR_MSG),
+        }
+    }
+}
+
+impl<A> Default for State<A>
+where
+    A: Application + 'static,
+{
+    fn default() -> Self {
+        let events = Arc::new(event::Bus::default());
+
+        Self {
+            components: RwLock::new(component::Registry::new(Arc::clone(&events))),
+            events,
+            paths: A::Paths::default(),
+            threads: RwLock::new(thread::Manager::default()),
+        }
+    }
 }
@@ -1,8 +1,12 @@
 //! Application (sub)command(s), i.e. app entry points
 
+mod verbosity;
+
 #[doc(hidden)]
 pub use abscissa_derive::Command;
 
+pub use self::verbosity::Verbosity;
+
 use crate::{runnable::Runnable, terminal};
 use clap::{FromArgMatches, Parser};
 use std::{env, ffi::OsString, fmt::Debug};
@@ -0,0 +1,116 @@
+//! Verbosity options.
+
+use clap::{ArgAction, Args};
+
+/// Counted verbosity options, which can be flattened into an application's
+/// command-line options:
+///
+/// ```ignore
+/// #[derive(clap::Parser, Command, Debug)]
+/// pub struct EntryPoint {
+///     #[command(flatten)]
+///     pub verbosity: Verbosity,
+/// }
+/// ```
+///
+/// The resulting verbosity determines the tracing filter and which status
+/// messages are printed:
+///
+/// | Options | Tracing filter | Status messages      |
+/// |---------|----------------|----------------------|
+/// | `-qq`   | `error`        | errors               |
+/// | `-q`    | `warn`         | errors and warnings  |
+/// | (none)  | `info`         | all                  |
+/// | `-v`    | `debug`        | all                  |
+/// | `-vv`   | `trace`        | all                  |
+#[derive(Args, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Verbosity {
+    /// Increase verbosity (can be repeated, e.g. `-vv`)
+    #[arg(short, long, action = ArgAction::Count, global = true, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Decrease verbosity (can be repeated, e.g. `-qq`)
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub quiet: u8,
+}
+
+impl Verbosity {
+    /// Get the verbosity level, where negative values are quieter.
+    pub fn level(&self) -> i8 {
+        let verbose = i8::try_from(self.verbose).unwrap_or(i8::MAX);
+        let quiet = i8::try_from(self.quiet).unwrap_or(i8::MAX);
+        verbose - quiet
+    }
+
+    /// Is quiet mode enabled?
+    pub fn is_quiet(&self) -> bool {
+        self.level() < 0
+    }
+
+    /// Get the tracing filter corresponding to this verbosity.
+    pub fn filter(&self) -> &'static str {
+        match self.level() {
+            ..=-2 => "error",
+            -1 => "warn",
+            0 => "info",
+            1 => "debug",
+            _ => "trace",
+        }
+    }
+
+    /// Get the tracing configuration corresponding to this verbosity.
+    ///
+    /// If the verbosity hasn't been changed, the default configuration is
+    /// used, which respects the `RUST_LOG` environment variable.
+    #[cfg(feature = "trace")]
+    pub fn tracing_config(&self) -> crate::trace::Config {
+        self.tracing_overrides()
+            .apply(crate::trace::Config::default())
+    }
+
+    /// Get the tracing overrides corresponding to this verbosity, which
+    /// override the level of the `[tracing]` configuration section unless
+    /// the verbosity hasn't been changed.
+    #[cfg(feature = "trace")]
+    pub fn tracing_overrides(&self) -> crate::trace::Overrides {
+        crate::trace::Overrides {
+            level: (self.level() != 0).then(|| self.filter().to_owned()),
+            format: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Verbosity;
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct Options {
+        #[command(flatten)]
+        verbosity: Verbosity,
+    }
+
+    /// Parse verbosity from the given arguments
+    fn parse(args: &[&str]) -> Verbosity {
+        Options::try_parse_from([&["app"], args].concat())
+            .unwrap()
+            .verbosity
+    }
+
+    #[test]
+    fn counted_flags() {
+        assert_eq!(parse(&[]).filter(), "info");
+        assert_eq!(parse(&["-v"]).filter(), "debug");
+        assert_eq!(parse(&["-vv"]).filter(), "trace");
+        assert_eq!(parse(&["-v", "--verbose", "-v"]).level(), 3);
+        assert_eq!(parse(&["-q"]).filter(), "warn");
+        assert_eq!(parse(&["-qqq"]).filter(), "error");
+        assert!(parse(&["--quiet"]).is_quiet());
+    }
+
+    #[test]
+    fn verbose_conflicts_with_quiet() {
+        assert!(Options::try_parse_from(["app", "-v", "-q"]).is_err());
+    }
+}
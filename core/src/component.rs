@@ -4,14 +4,37 @@
 
 #![allow(unused_variables)]
 
+pub mod filter;
+pub mod graph;
+mod guard;
 mod handle;
+pub mod health;
 mod id;
 pub mod registry;
-
-pub use self::{handle::Handle, id::Id, registry::Registry};
+pub mod section;
+mod table;
+pub mod timing;
+
+pub use self::{
+    filter::Filter,
+    guard::{Ref, RefMut},
+    handle::Handle,
+    health::Health,
+    id::Id,
+    registry::Registry,
+    section::ConfigSection,
+};
 pub use abscissa_derive::{Component, Injectable};
 
-use crate::{FrameworkError, Version, application::Application, shutdown::Shutdown};
+use crate::{
+    FrameworkError,
+    FrameworkErrorKind::ComponentError,
+    Version,
+    application::Application,
+    config::Section,
+    event::{self, Subscription},
+    shutdown::Shutdown,
+};
 use std::{any::Any, cmp::Ordering, fmt::Debug, slice::Iter};
 
 /// The aspect of application components associated with dependency injection.
@@ -47,6 +70,27 @@ where
     ) -> Result<(), FrameworkError> {
         unimplemented!();
     }
+
+    /// Name of this component's section of the application's configuration
+    /// file, if it has one.
+    ///
+    /// See the [`section`] module for more information.
+    fn config_section(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Load this component's section of the configuration file, i.e. the
+    /// section named by `config_section` above.
+    ///
+    /// Called when the application's configuration is loaded, immediately
+    /// before this component's `after_config` callback.
+    fn load_config_section(&mut self, section: &Section) -> Result<(), FrameworkError> {
+        fail!(
+            ComponentError,
+            "component {} has a config section but doesn't load it",
+            self.id()
+        );
+    }
 }
 
 /// Application components.
@@ -61,7 +105,12 @@ where
 /// `register_dependency` callback is called for each dependency returned
 /// by the `dependencies` method.
 ///
-/// Additionally, they receive a callback prior to application shutdown.
+/// Additionally, they receive a callback prior to application shutdown, and
+/// can be individually restarted at runtime, in which case the components
+/// which depend on them receive a `dependency_replaced` callback.
+///
+/// Components can also report their [`Health`], which is aggregated by the
+/// [`Registry`] into a [`health::Report`].
 ///
 /// ## Custom Derive
 ///
@@ -104,10 +153,38 @@ where
         Ok(())
     }
 
+    /// Subscribe to events on the application's event bus.
+    ///
+    /// Called when this component is registered. The returned subscriptions
+    /// are removed if the component is later restarted.
+    fn subscribe(&mut self, events: &event::Bus) -> Result<Vec<Subscription>, FrameworkError> {
+        Ok(vec![])
+    }
+
+    /// Lifecycle event called when a dependency of this component has been
+    /// replaced at runtime (see [`Registry::restart`]).
+    ///
+    /// By default this re-runs dependency injection for the new instance of
+    /// the dependency via `register_dependency`.
+    fn dependency_replaced(
+        &mut self,
+        handle: Handle,
+        dependency: &mut dyn Component<A>,
+    ) -> Result<(), FrameworkError> {
+        self.register_dependency(handle, dependency)
+    }
+
     /// Perform any tasks which should occur before the app exits
     fn before_shutdown(&self, kind: Shutdown) -> Result<(), FrameworkError> {
         Ok(())
     }
+
+    /// Report the current health of this component.
+    ///
+    /// Components are assumed to be healthy unless they override this.
+    fn health(&self) -> Health {
+        Health::Healthy
+    }
 }
 
 impl<A> PartialEq for Box<dyn Component<A>>
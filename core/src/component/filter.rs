@@ -0,0 +1,110 @@
+//! Enabling and disabling components from configuration.
+//!
+//! Applications which ship optional subsystems can select which components
+//! get registered using the framework-level `[components]` section of their
+//! configuration file, keyed by component [`Id`]:
+//!
+//! ```toml
+//! [components]
+//! # Only register these components (if absent, all components are enabled)
+//! enabled = ["myapp::components::Database", "myapp::components::Metrics"]
+//!
+//! # Never register these components
+//! disabled = ["myapp::components::Metrics"]
+//! ```
+//!
+//! The framework's own components (i.e. those in `abscissa_core`) are
+//! always registered. Disabling a component which another enabled component
+//! depends on is an error.
+
+use super::id::Id;
+use crate::{
+    FrameworkError,
+    FrameworkErrorKind::{ComponentError, ConfigError},
+    config::Sections,
+};
+use serde::Deserialize;
+
+/// Name of the configuration section used to enable/disable components.
+pub const SECTION: &str = "components";
+
+/// Prefix of the IDs of the framework's own components.
+const FRAMEWORK_PREFIX: &str = "abscissa_core::";
+
+/// Filter determining which components are registered, as configured by the
+/// `[components]` section of the configuration file.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Filter {
+    /// IDs of the components to register (if absent, all are registered)
+    pub enabled: Option<Vec<String>>,
+
+    /// IDs of the components to never register
+    pub disabled: Vec<String>,
+}
+
+impl Filter {
+    /// Parse the `[components]` section of the given configuration sections,
+    /// if present.
+    pub fn from_sections(sections: &Sections) -> Result<Self, FrameworkError> {
+        let section = match sections.get(SECTION) {
+            Some(section) => section,
+            None => return Ok(Self::default()),
+        };
+
+        section.clone().try_into().map_err(|e| {
+            format_err!(
+                ConfigError,
+                "error parsing `[{}]` configuration section: {}",
+                SECTION,
+                e
+            )
+            .into()
+        })
+    }
+
+    /// Is the component with the given ID enabled?
+    pub fn is_enabled(&self, id: Id) -> bool {
+        if is_framework_component(id) {
+            return true;
+        }
+
+        if self.disabled.iter().any(|name| name == id.as_ref()) {
+            return false;
+        }
+
+        self.enabled
+            .as_ref()
+            .is_none_or(|enabled| enabled.iter().any(|name| name == id.as_ref()))
+    }
+
+    /// Ensure every component named by this filter is among the given IDs,
+    /// and that none of the framework's components are disabled.
+    pub fn validate(&self, ids: &[Id]) -> Result<(), FrameworkError> {
+        for name in self.enabled.iter().flatten().chain(&self.disabled) {
+            ensure!(
+                ids.iter().any(|id| id.as_ref() == name),
+                ConfigError,
+                "unknown component in `[{}]` configuration section: {}",
+                SECTION,
+                name
+            );
+        }
+
+        for name in &self.disabled {
+            ensure!(
+                !name.starts_with(FRAMEWORK_PREFIX),
+                ComponentError,
+                "framework component can't be disabled: {}",
+                name
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Is the given ID one of the framework's own components?
+fn is_framework_component(id: Id) -> bool {
+    id.as_ref().starts_with(FRAMEWORK_PREFIX)
+}
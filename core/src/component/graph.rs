@@ -0,0 +1,190 @@
+//! Component dependency graphs.
+//!
+//! Exports the graph of registered components (with their versions,
+//! dependency edges, and resolved start order) in [Graphviz DOT] and
+//! [Mermaid] formats, e.g. for rendering in documentation or checking
+//! against a known-good graph in CI.
+//!
+//! [Graphviz DOT]: https://graphviz.org/doc/info/lang.html
+//! [Mermaid]: https://mermaid.js.org/syntax/flowchart.html
+
+use super::id::Id;
+use crate::{Map, Version};
+use std::fmt::Write;
+
+/// Formats in which a component [`Graph`] can be rendered.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// Component registered in a [`Graph`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Node {
+    /// Component identifier
+    pub id: Id,
+
+    /// Component version
+    pub version: Version,
+
+    /// Position of this component in the resolved start order
+    pub order: usize,
+}
+
+/// Dependency edge between two components in a [`Graph`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Edge {
+    /// Component which has the dependency
+    pub dependent: Id,
+
+    /// Component being depended upon
+    pub dependency: Id,
+}
+
+/// Dependency graph of the components in a registry.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Graph {
+    /// Registered components, in their resolved start order
+    nodes: Vec<Node>,
+
+    /// Dependency edges between components
+    edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Create a new graph from the given nodes and edges.
+    pub fn new(nodes: Vec<Node>, edges: Vec<Edge>) -> Self {
+        Self { nodes, edges }
+    }
+
+    /// Registered components, in their resolved start order.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Dependency edges between components.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Render this graph in the given format.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Dot => self.to_dot(),
+            Format::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Render this graph in Graphviz DOT format.
+    ///
+    /// Dependencies which aren't registered are drawn with dashed outlines.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph components {\n    rankdir=LR;\n");
+
+        for node in &self.nodes {
+            writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\\nv{}\\n#{}\"];",
+                node.id, node.id, node.version, node.order
+            )
+            .unwrap();
+        }
+
+        for id in self.unregistered() {
+            writeln!(dot, "    \"{}\" [style=dashed];", id).unwrap();
+        }
+
+        for edge in &self.edges {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\";",
+                edge.dependent, edge.dependency
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render this graph as a Mermaid flowchart.
+    ///
+    /// Dependencies which aren't registered are drawn with dashed outlines.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        let unregistered = self.unregistered();
+        let keys = self.mermaid_keys(&unregistered);
+        let key = |id: Id| &keys[&id];
+
+        for node in &self.nodes {
+            writeln!(
+                mermaid,
+                "    {}[\"{}<br/>v{}<br/>#{}\"]",
+                key(node.id),
+                node.id,
+                node.version,
+                node.order
+            )
+            .unwrap();
+        }
+
+        for id in &unregistered {
+            writeln!(mermaid, "    {}[\"{}\"]", key(*id), id).unwrap();
+        }
+
+        for edge in &self.edges {
+            writeln!(
+                mermaid,
+                "    {} --> {}",
+                key(edge.dependent),
+                key(edge.dependency)
+            )
+            .unwrap();
+        }
+
+        for id in &unregistered {
+            writeln!(mermaid, "    style {} stroke-dasharray: 5 5", key(*id)).unwrap();
+        }
+
+        mermaid
+    }
+
+    /// Dependencies referenced by edges which aren't registered.
+    fn unregistered(&self) -> Vec<Id> {
+        let mut ids = self
+            .edges
+            .iter()
+            .map(|edge| edge.dependency)
+            .filter(|id| !self.nodes.iter().any(|node| node.id == *id))
+            .collect::<Vec<_>>();
+
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Keys identifying each component (including the given unregistered
+    /// dependencies) in a Mermaid flowchart.
+    ///
+    /// Mermaid node IDs can't contain `::`, so these are derived from each
+    /// component's position in the graph instead.
+    fn mermaid_keys(&self, unregistered: &[Id]) -> Map<Id, String> {
+        let registered = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (node.id, format!("c{}", position)));
+
+        let unregistered = unregistered
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (*id, format!("u{}", position)));
+
+        registered.chain(unregistered).collect()
+    }
+}
@@ -0,0 +1,126 @@
+//! Typed references to individual components.
+//!
+//! Each component in the [`Registry`] is individually locked, so holding a
+//! reference to one component does not block access to unrelated ones, nor
+//! does it hold a lock on the registry itself.
+//!
+//! [`Registry`]: super::Registry
+
+use super::Component;
+use crate::application::Application;
+use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, RawRwLock};
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// Error message to use for component type mismatch panics.
+const TYPE_ERR_MSG: &str = "component type mismatch";
+
+/// Typed read-only reference to a component of type `C`.
+pub struct Ref<A: Application + 'static, C> {
+    /// Read guard on the component
+    guard: ArcRwLockReadGuard<RawRwLock, Box<dyn Component<A>>>,
+
+    /// Type of the component
+    component: PhantomData<fn() -> C>,
+}
+
+impl<A, C> Ref<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    /// Create a typed reference from the given guard, if its component is
+    /// of type `C`.
+    pub(super) fn new(guard: ArcRwLockReadGuard<RawRwLock, Box<dyn Component<A>>>) -> Option<Self> {
+        (**guard).as_any().is::<C>().then_some(Self {
+            guard,
+            component: PhantomData,
+        })
+    }
+}
+
+impl<A, C> Deref for Ref<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        (**self.guard).as_any().downcast_ref().expect(TYPE_ERR_MSG)
+    }
+}
+
+impl<A, C> fmt::Debug for Ref<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Typed mutable reference to a component of type `C`.
+pub struct RefMut<A: Application + 'static, C> {
+    /// Write guard on the component
+    guard: ArcRwLockWriteGuard<RawRwLock, Box<dyn Component<A>>>,
+
+    /// Type of the component
+    component: PhantomData<fn() -> C>,
+}
+
+impl<A, C> RefMut<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    /// Create a typed mutable reference from the given guard, if its
+    /// component is of type `C`.
+    pub(super) fn new(
+        guard: ArcRwLockWriteGuard<RawRwLock, Box<dyn Component<A>>>,
+    ) -> Option<Self> {
+        (**guard).as_any().is::<C>().then_some(Self {
+            guard,
+            component: PhantomData,
+        })
+    }
+}
+
+impl<A, C> Deref for RefMut<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        (**self.guard).as_any().downcast_ref().expect(TYPE_ERR_MSG)
+    }
+}
+
+impl<A, C> DerefMut for RefMut<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    fn deref_mut(&mut self) -> &mut C {
+        (**self.guard)
+            .as_mut_any()
+            .downcast_mut()
+            .expect(TYPE_ERR_MSG)
+    }
+}
+
+impl<A, C> fmt::Debug for RefMut<A, C>
+where
+    A: Application + 'static,
+    C: Component<A>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -1,7 +1,7 @@
 //! Component handles: opaque references to registered components
 
 use super::{id::Id, registry::Index};
-use std::fmt;
+use std::{cmp::Ordering, fmt, marker::PhantomData};
 
 /// Component handles are references to components which have been registered
 /// with a `component::Registry`.
@@ -9,28 +9,71 @@ use std::fmt;
 /// However, unlike normal Rust references, component handles are a "weak"
 /// reference which is not checked by the borrow checker. This allows for
 /// complex reference graphs which are otherwise inexpressible in Rust.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct Handle {
+///
+/// Handles can optionally be typed (i.e. `Handle<C>`), in which case they can
+/// be resolved to a reference to a component of type `C` without downcasting
+/// (see `Registry::resolve`).
+pub struct Handle<C = ()> {
     /// Component name
     name: Id,
 
     /// Registry index
     pub(super) index: Index,
+
+    /// Type of the component (if typed)
+    component: PhantomData<fn() -> C>,
 }
 
-impl Handle {
+impl<C> Handle<C> {
     /// Create a new handle from a component's name and index
     pub(crate) fn new(name: Id, index: Index) -> Self {
-        Self { name, index }
+        Self {
+            name,
+            index,
+            component: PhantomData,
+        }
     }
 
     /// Get the identifier of the component this handle points to
     pub fn id(self) -> Id {
         self.name
     }
+
+    /// Convert this handle into an untyped handle
+    pub fn untyped(self) -> Handle {
+        Handle::new(self.name, self.index)
+    }
+}
+
+impl<C> Copy for Handle<C> {}
+
+impl<C> Clone for Handle<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Eq for Handle<C> {}
+
+impl<C> PartialEq for Handle<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.index == other.index
+    }
+}
+
+impl<C> Ord for Handle<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.name, self.index).cmp(&(other.name, other.index))
+    }
+}
+
+impl<C> PartialOrd for Handle<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-impl fmt::Debug for Handle {
+impl<C> fmt::Debug for Handle<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Handle({})", self.id().as_ref())
     }
@@ -0,0 +1,221 @@
+//! Component health checks.
+//!
+//! Components report their health via the [`Component::health`] callback,
+//! and the [`Registry`] aggregates the results of all registered components
+//! into a [`Report`] which can be displayed as a table or serialized as JSON.
+//!
+//! [`Component::health`]: super::Component::health
+//! [`Registry`]: super::Registry
+
+use super::{id::Id, table};
+use crate::{FrameworkError, Version, terminal};
+use serde::Serialize;
+use std::{
+    fmt::{self, Display},
+    io::Write,
+    slice,
+};
+
+/// Column headings used when displaying a [`Report`] as a table.
+const TABLE_HEADINGS: [&str; 3] = ["COMPONENT", "VERSION", "HEALTH"];
+
+/// Health of an individual component.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", content = "details", rename_all = "lowercase")]
+pub enum Health {
+    /// Component is operating normally.
+    #[default]
+    Healthy,
+
+    /// Component's health couldn't be checked (e.g. because it's currently
+    /// mutably borrowed).
+    Unknown(String),
+
+    /// Component is operational, but in a reduced capacity (e.g. a connection
+    /// pool which has lost some of its connections).
+    Degraded(String),
+
+    /// Component is unable to operate.
+    Unhealthy(String),
+}
+
+impl Health {
+    /// Is this component healthy?
+    pub fn is_healthy(&self) -> bool {
+        *self == Health::Healthy
+    }
+
+    /// Is this component unhealthy?
+    pub fn is_unhealthy(&self) -> bool {
+        matches!(self, Health::Unhealthy(_))
+    }
+
+    /// Get the details describing why a component isn't healthy (if any).
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            Health::Healthy => None,
+            Health::Unknown(details) | Health::Degraded(details) | Health::Unhealthy(details) => {
+                Some(details)
+            }
+        }
+    }
+
+    /// Name of this health status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Health::Healthy => "healthy",
+            Health::Unknown(_) => "unknown",
+            Health::Degraded(_) => "degraded",
+            Health::Unhealthy(_) => "unhealthy",
+        }
+    }
+
+    /// Severity of this health status, used to compute aggregate health.
+    fn severity(&self) -> u8 {
+        match self {
+            Health::Healthy => 0,
+            Health::Unknown(_) => 1,
+            Health::Degraded(_) => 2,
+            Health::Unhealthy(_) => 3,
+        }
+    }
+}
+
+impl Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())?;
+
+        if let Some(details) = self.details() {
+            write!(f, ": {}", details)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Output formats for health reports.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable table
+    #[default]
+    Table,
+
+    /// JSON document
+    Json,
+}
+
+/// Health of a particular registered component.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Entry {
+    /// Component identifier
+    pub id: Id,
+
+    /// Component version
+    pub version: Version,
+
+    /// Component health
+    pub health: Health,
+}
+
+/// Health report for all components in a registry.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct Report {
+    /// Aggregate health of all components
+    health: Health,
+
+    /// Health of each individual component (in dependency order)
+    components: Vec<Entry>,
+}
+
+impl Report {
+    /// Create a new report from the given entries.
+    pub fn new(components: Vec<Entry>) -> Self {
+        let mut unhealthy = 0;
+        let mut degraded = 0;
+        let mut unknown = 0;
+
+        for entry in &components {
+            match entry.health {
+                Health::Healthy => (),
+                Health::Unknown(_) => unknown += 1,
+                Health::Degraded(_) => degraded += 1,
+                Health::Unhealthy(_) => unhealthy += 1,
+            }
+        }
+
+        let worst = components
+            .iter()
+            .map(|entry| &entry.health)
+            .max_by_key(|health| health.severity());
+
+        let health = match worst {
+            Some(Health::Unhealthy(_)) => Health::Unhealthy(format!(
+                "{} unhealthy, {} degraded component(s)",
+                unhealthy, degraded
+            )),
+            Some(Health::Degraded(_)) => {
+                Health::Degraded(format!("{} degraded component(s)", degraded))
+            }
+            Some(Health::Unknown(_)) => {
+                Health::Unknown(format!("{} component(s) of unknown health", unknown))
+            }
+            _ => Health::Healthy,
+        };
+
+        Self { health, components }
+    }
+
+    /// Aggregate health of all components: the health of the least healthy
+    /// component in the registry.
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+
+    /// Iterate over the health of the individual components.
+    pub fn iter(&self) -> slice::Iter<'_, Entry> {
+        self.components.iter()
+    }
+
+    /// Serialize this report as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("health report serialization failed")
+    }
+
+    /// Print this report to the terminal's standard output.
+    pub fn print(&self, format: Format) -> Result<(), FrameworkError> {
+        let mut stdout = terminal::stdout().lock();
+
+        match format {
+            Format::Table => write!(stdout, "{}", self),
+            Format::Json => writeln!(stdout, "{}", self.to_json()),
+        }?;
+
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self.components.iter().map(|entry| {
+            [
+                entry.id.to_string(),
+                entry.version.to_string(),
+                entry.health.to_string(),
+            ]
+        });
+
+        table::write(f, TABLE_HEADINGS, rows)?;
+        writeln!(f)?;
+        writeln!(f, "overall: {}", self.health)
+    }
+}
+
+impl<'a> IntoIterator for &'a Report {
+    type Item = &'a Entry;
+    type IntoIter = slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> slice::Iter<'a, Entry> {
+        self.iter()
+    }
+}
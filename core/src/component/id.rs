@@ -3,6 +3,7 @@
 //! By convention these are Rust paths to the component types
 // TODO(tarcieri): enforce this convention via e.g. custom derive?
 
+use serde::{Serialize, Serializer};
 use std::fmt;
 
 /// Identifier for an individual component
@@ -31,3 +32,9 @@ impl fmt::Display for Id {
         write!(f, "{}", self.0)
     }
 }
+
+impl Serialize for Id {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
@@ -1,20 +1,32 @@
 //! Abscissa's component registry
 
-use super::{Component, handle::Handle, id::Id};
+use super::{
+    Component,
+    filter::Filter,
+    graph,
+    guard::{Ref, RefMut},
+    handle::Handle,
+    health,
+    id::Id,
+    timing::{self, Phase, Timing},
+};
 use crate::{
     FrameworkError,
     FrameworkErrorKind::ComponentError,
-    Map,
+    Map, Version,
     application::{self, Application},
+    config::Sections,
+    event::{self, Subscription},
     shutdown::Shutdown,
 };
-use std::{any::TypeId, borrow::Borrow, slice, sync};
-
-/// Iterator over the components in the registry.
-pub type Iter<'a, A> = slice::Iter<'a, Box<dyn Component<A>>>;
-
-/// Mutable iterator over the components in the registry.
-pub type IterMut<'a, A> = slice::IterMut<'a, Box<dyn Component<A>>>;
+use parking_lot::{MappedRwLockReadGuard, Mutex, RwLock};
+use std::{
+    any::{TypeId, type_name},
+    borrow::Borrow,
+    iter, mem, slice,
+    sync::{self, Arc},
+    time::Instant,
+};
 
 /// Reader guard for the registry.
 pub type Reader<'a, A> = sync::RwLockReadGuard<'a, Registry<A>>;
@@ -22,12 +34,46 @@ pub type Reader<'a, A> = sync::RwLockReadGuard<'a, Registry<A>>;
 /// Writer guard for the registry.
 pub type Writer<'a, A> = sync::RwLockWriteGuard<'a, Registry<A>>;
 
+/// Reader guard for an individual component.
+pub type ComponentReader<'a, A> = parking_lot::RwLockReadGuard<'a, Box<dyn Component<A>>>;
+
+/// Writer guard for an individual component.
+pub type ComponentWriter<'a, A> = parking_lot::RwLockWriteGuard<'a, Box<dyn Component<A>>>;
+
+/// Individually locked component, shared with any outstanding references.
+type Slot<A> = Arc<RwLock<Box<dyn Component<A>>>>;
+
 /// Index of component identifiers to their arena locations.
 type IdMap = Map<Id, Index>;
 
 /// Index of component type IDs to their arena locations.
 type TypeMap = Map<TypeId, Index>;
 
+/// Information about a registered component which can be read without
+/// locking it.
+#[derive(Clone, Debug)]
+struct Metadata {
+    /// Component identifier
+    id: Id,
+
+    /// Component version
+    version: Version,
+
+    /// Identifiers of the component's dependencies
+    dependencies: Vec<Id>,
+}
+
+impl Metadata {
+    /// Record the metadata of the given component.
+    fn new<A: Application>(component: &dyn Component<A>) -> Self {
+        Self {
+            id: component.id(),
+            version: component.version(),
+            dependencies: component.dependencies().copied().collect(),
+        }
+    }
+}
+
 /// Index type providing efficient access to a particular component.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub struct Index(usize);
@@ -37,23 +83,96 @@ pub struct Index(usize);
 ///
 /// Components are sorted according to a dependency ordering, started
 /// in-order, and at application termination time, shut down in reverse order.
-#[derive(Debug, Default)]
+///
+/// Each registry is tied to the event bus its components subscribe to, so
+/// there's intentionally no `Default` impl: the application's registry is
+/// created by its [`State`], which shares its own bus with it.
+///
+/// Each component is locked individually. Registry methods which take
+/// `&self` never block on a component: those returning references to
+/// components panic if it's mutably borrowed (like [`RefCell::borrow`]),
+/// [`Registry::health_report`] reports its health as unknown, and
+/// [`Registry::shutdown`] fails. Methods which take `&mut self` panic if a
+/// component is borrowed at all (like [`RefCell::borrow_mut`]). Drop typed
+/// [`Ref`] and [`RefMut`] references before calling these methods from the
+/// same thread.
+///
+/// Only [`Registry::component`], [`Registry::component_mut`] and the
+/// corresponding `resolve` methods wait for a component to become
+/// available, so they can be used to share components between threads, but
+/// deadlock if the same thread already holds a conflicting reference.
+///
+/// [`State`]: crate::application::State
+/// [`RefCell::borrow`]: std::cell::RefCell::borrow
+/// [`RefCell::borrow_mut`]: std::cell::RefCell::borrow_mut
+#[derive(Debug)]
 pub struct Registry<A: Application + 'static> {
     /// Generational arena of registered components
-    components: Vec<Box<dyn Component<A>>>,
+    components: Vec<Slot<A>>,
+
+    /// Metadata of each component, indexed like `components`
+    metadata: Vec<Metadata>,
 
     /// Map of component identifiers to their indexes
     id_map: IdMap,
 
     /// Map of component types to their identifiers
     type_map: TypeMap,
+
+    /// Raw sections of the application's configuration file
+    config_sections: Sections,
+
+    /// Filter determining which components are registered
+    filter: Filter,
+
+    /// Event bus shared with the application state
+    events: Arc<event::Bus>,
+
+    /// Event subscriptions made by each component
+    subscriptions: Map<Id, Vec<Subscription>>,
+
+    /// Lifecycle timings of each component (most recent last)
+    timings: Mutex<Vec<Timing>>,
 }
 
 impl<A> Registry<A>
 where
     A: Application + 'static,
 {
-    /// Register components, determining their dependency order
+    /// Create a new registry whose components subscribe to events on the
+    /// given event bus.
+    pub fn new(events: Arc<event::Bus>) -> Self {
+        Self {
+            components: vec![],
+            metadata: vec![],
+            id_map: IdMap::default(),
+            type_map: TypeMap::default(),
+            config_sections: Sections::default(),
+            filter: Filter::default(),
+            events,
+            subscriptions: Map::default(),
+            timings: Mutex::default(),
+        }
+    }
+
+    /// Construct a component, recording how long construction takes.
+    pub fn construct<C, F>(&self, f: F) -> Result<Box<dyn Component<A>>, FrameworkError>
+    where
+        C: Component<A>,
+        F: FnOnce() -> Result<C, FrameworkError>,
+    {
+        self.construct_boxed(|| Ok(Box::new(f()?)))
+    }
+
+    /// Borrow the event bus used by this registry.
+    pub fn events(&self) -> &Arc<event::Bus> {
+        &self.events
+    }
+
+    /// Register components, determining their dependency order.
+    ///
+    /// Components which are disabled by the `[components]` section of the
+    /// configuration file (see [`Filter`]) are skipped.
     pub fn register<I>(&mut self, components: I) -> Result<(), FrameworkError>
     where
         I: IntoIterator<Item = Box<dyn Component<A>>>,
@@ -65,7 +184,28 @@ where
             "no support for registering additional components (yet)"
         );
 
-        let mut components = components.into_iter().collect::<Vec<_>>();
+        let components = components.into_iter().collect::<Vec<_>>();
+        let ids = components
+            .iter()
+            .map(|component| component.id())
+            .collect::<Vec<_>>();
+        self.filter.validate(&ids)?;
+
+        let (mut components, disabled): (Vec<_>, Vec<_>) = components
+            .into_iter()
+            .partition(|component| self.filter.is_enabled(component.id()));
+
+        for component in &components {
+            for dep_id in component.dependencies() {
+                ensure!(
+                    !disabled.iter().any(|dep| dep.id() == *dep_id),
+                    ComponentError,
+                    "component {} depends on disabled component: {}",
+                    component.id(),
+                    dep_id
+                );
+            }
+        }
 
         components.sort_by(|a, b| {
             a.partial_cmp(b)
@@ -79,15 +219,29 @@ where
         Ok(())
     }
 
+    /// Set the raw sections of the application's configuration file, which
+    /// are delivered to components which declare their own configuration
+    /// section when `after_config` is fired.
+    ///
+    /// If set prior to registration, the `[components]` section determines
+    /// which components are registered.
+    pub fn set_config_sections(&mut self, sections: Sections) -> Result<(), FrameworkError> {
+        self.filter = Filter::from_sections(&sections)?;
+        self.config_sections = sections;
+        Ok(())
+    }
+
     /// Callback fired by application when configuration has been loaded
     pub fn after_config(&mut self, config: &A::Cfg) -> Result<(), FrameworkError> {
         let mut component_indexes: Vec<(Index, Vec<Index>)> = vec![];
 
-        for (index, component) in self.components.iter_mut().enumerate() {
+        for (index, slot) in self.components.iter().enumerate() {
+            let mut component = slot.write();
+
             // Fire the `after_config` callback for each subcomponent.
             //
             // Note that these are fired for *all* components prior to subcomponent registration
-            component.after_config(config)?;
+            self.configure(&mut **component, config)?;
 
             let mut dep_indexes = vec![];
 
@@ -105,19 +259,174 @@ where
         // Fire the `register_dependency` callbacks for each component's dependencies
         for (component_index, dep_indexes) in component_indexes {
             for dep_index in dep_indexes {
-                if let (Some(component), Some(dep)) = self.get2_mut(component_index, dep_index) {
-                    let dep_handle = Handle::new(dep.id(), dep_index);
-                    component.register_dependency(dep_handle, dep.as_mut())?;
-                } else {
-                    // In theory we just looked all of these up and they should always be valid
-                    unreachable!();
-                }
+                let (mut component, mut dep) = self.get2_mut(component_index, dep_index)?;
+                let dep_handle = Handle::new(dep.id(), dep_index);
+                let phase = Phase::RegisterDependency(dep.id());
+                self.timed(component.id(), phase, || {
+                    component.register_dependency(dep_handle, &mut **dep)
+                })?;
             }
         }
 
         Ok(())
     }
 
+    /// Restart an individual component at runtime, e.g. a connection pool
+    /// which has entered a failed state.
+    ///
+    /// The replacement is constructed, subscribed to events and receives the
+    /// `after_config` callback first, after which the currently registered
+    /// component with the given ID is shut down with the given kind of
+    /// [`Shutdown`] and swapped for it. The dependencies of the replacement
+    /// are then injected, and the components which depend on it receive a
+    /// `dependency_replaced` callback.
+    ///
+    /// If preparing the replacement fails (or it has a different ID or
+    /// type), the existing component remains registered and running, and an
+    /// error is returned.
+    ///
+    /// Restarting locks the replaced component along with its dependencies
+    /// and dependents, so it fails with an error (rather than deadlocking)
+    /// while any component is still borrowed through a typed [`Ref`] or
+    /// [`RefMut`]: drop those before calling this method.
+    pub fn restart<F>(
+        &mut self,
+        id: Id,
+        shutdown: Shutdown,
+        config: &A::Cfg,
+        construct: F,
+    ) -> Result<Handle, FrameworkError>
+    where
+        F: FnOnce() -> Result<Box<dyn Component<A>>, FrameworkError>,
+    {
+        let handle = self
+            .get_handle_by_id(id)
+            .ok_or_else(|| format_err!(ComponentError, "unregistered component ID: {}", id))?;
+
+        let index = handle.index;
+        let mut replacement = self.construct_boxed(construct)?;
+
+        ensure!(
+            replacement.id() == id,
+            ComponentError,
+            "replacement for {} has mismatched ID: {}",
+            id,
+            replacement.id()
+        );
+
+        if let Some(borrowed) = self
+            .id_map
+            .iter()
+            .find(|(_, index)| Arc::strong_count(&self.components[index.0]) > 1)
+            .map(|(borrowed, _)| *borrowed)
+        {
+            fail!(
+                ComponentError,
+                "can't restart {} while {} is borrowed",
+                id,
+                borrowed
+            );
+        }
+
+        let mut component = self.components[index.0].write();
+
+        ensure!(
+            (*replacement).type_id() == (**component).type_id(),
+            ComponentError,
+            "replacement for {} has mismatched type",
+            id
+        );
+
+        let dep_indexes = replacement
+            .dependencies()
+            .map(|dep_id| {
+                self.id_map.get(dep_id).copied().ok_or_else(|| {
+                    format_err!(ComponentError, "unregistered dependency ID: {}", dep_id)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let metadata = Metadata::new(&*replacement);
+
+        // Prepare the replacement before shutting down the existing component,
+        // which remains registered if any of these steps fail
+        let subscriptions = replacement.subscribe(&self.events)?;
+
+        let prepared = self.configure(&mut *replacement, config).and_then(|()| {
+            self.timed(id, Phase::BeforeShutdown, || {
+                component.before_shutdown(shutdown)
+            })
+        });
+
+        if let Err(e) = prepared {
+            for subscription in subscriptions {
+                self.events.unsubscribe(subscription);
+            }
+
+            return Err(e);
+        }
+
+        debug!(
+            "replacing component: {} (v{} => v{})",
+            id,
+            component.version(),
+            replacement.version()
+        );
+
+        drop(mem::replace(&mut *component, replacement));
+        drop(component);
+        self.metadata[index.0] = metadata;
+
+        for subscription in self
+            .subscriptions
+            .insert(id, subscriptions)
+            .unwrap_or_default()
+        {
+            self.events.unsubscribe(subscription);
+        }
+
+        // Inject the dependencies of the replacement
+        for dep_index in dep_indexes {
+            let (mut component, mut dep) = self.get2_mut(index, dep_index)?;
+            let dep_handle = Handle::new(dep.id(), dep_index);
+            let phase = Phase::RegisterDependency(dep.id());
+            self.timed(id, phase, || {
+                component.register_dependency(dep_handle, &mut **dep)
+            })?;
+        }
+
+        // Notify the components which depend on the replacement
+        let dependent_indexes = self
+            .metadata
+            .iter()
+            .enumerate()
+            .filter(|(_, metadata)| metadata.dependencies.contains(&id))
+            .map(|(dependent_index, _)| Index(dependent_index))
+            .collect::<Vec<_>>();
+
+        for dependent_index in dependent_indexes {
+            let (mut dependent, mut component) = self.get2_mut(dependent_index, index)?;
+            self.timed(dependent.id(), Phase::RegisterDependency(id), || {
+                dependent.dependency_replaced(handle, &mut **component)
+            })?;
+        }
+
+        Ok(handle)
+    }
+
+    /// Replace a registered component with the given instance, which must
+    /// have the same ID and type.
+    ///
+    /// See [`Registry::restart`] for more information.
+    pub fn replace(
+        &mut self,
+        replacement: Box<dyn Component<A>>,
+        shutdown: Shutdown,
+        config: &A::Cfg,
+    ) -> Result<Handle, FrameworkError> {
+        self.restart(replacement.id(), shutdown, config, || Ok(replacement))
+    }
+
     /// Get the number of currently registered components
     pub fn len(&self) -> usize {
         self.components.len()
@@ -128,14 +437,24 @@ where
         self.components.is_empty()
     }
 
-    /// Get a component reference by its handle
-    pub fn get(&self, handle: Handle) -> Option<&dyn Component<A>> {
-        self.components.get(handle.index.0).map(AsRef::as_ref)
+    /// Get a component reference by its handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is mutably borrowed through a typed [`RefMut`].
+    pub fn get(&self, handle: Handle) -> Option<ComponentReader<'_, A>> {
+        self.read(handle.index)
     }
 
-    /// Get a mutable component reference by its handle
+    /// Get a mutable component reference by its handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is borrowed through a typed [`Ref`] or
+    /// [`RefMut`].
     pub fn get_mut(&mut self, handle: Handle) -> Option<&mut (dyn Component<A> + 'static)> {
-        self.components.get_mut(handle.index.0).map(AsMut::as_mut)
+        self.slot_mut(handle.index)
+            .map(|component| &mut **component)
     }
 
     /// Get a component's handle by its ID
@@ -148,55 +467,284 @@ where
         self.get_handle_by_id(component.id())
     }
 
-    /// Get a component ref by its ID
-    pub fn get_by_id(&self, id: Id) -> Option<&dyn Component<A>> {
+    /// Get a component ref by its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is mutably borrowed through a typed [`RefMut`].
+    pub fn get_by_id(&self, id: Id) -> Option<ComponentReader<'_, A>> {
         self.get(self.get_handle_by_id(id)?)
     }
 
-    /// Get a mutable component ref by its ID
+    /// Get a mutable component ref by its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is borrowed through a typed [`Ref`] or
+    /// [`RefMut`].
     pub fn get_mut_by_id(&mut self, id: Id) -> Option<&mut (dyn Component<A> + 'static)> {
         self.get_mut(self.get_handle_by_id(id)?)
     }
 
+    /// Get a typed handle to the component of type `C`.
+    pub fn handle<C>(&self) -> Result<Handle<C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        let index = self.type_index::<C>()?;
+        Ok(Handle::new(self.metadata[index.0].id, index))
+    }
+
+    /// Get a typed reference to the component of type `C`.
+    ///
+    /// Only the component itself is locked, so the returned reference does
+    /// not borrow the registry and doesn't block access to other components.
+    pub fn component<C>(&self) -> Result<Ref<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.resolve(self.handle::<C>()?)
+    }
+
+    /// Get a typed mutable reference to the component of type `C`.
+    ///
+    /// Only the component itself is locked, so the returned reference does
+    /// not borrow the registry and doesn't block access to other components.
+    pub fn component_mut<C>(&self) -> Result<RefMut<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.resolve_mut(self.handle::<C>()?)
+    }
+
+    /// Resolve a typed handle to a reference to its component.
+    pub fn resolve<C>(&self, handle: Handle<C>) -> Result<Ref<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.components
+            .get(handle.index.0)
+            .and_then(|slot| Ref::new(slot.read_arc()))
+            .ok_or_else(|| unregistered::<C>(handle.id()))
+    }
+
+    /// Resolve a typed handle to a mutable reference to its component.
+    pub fn resolve_mut<C>(&self, handle: Handle<C>) -> Result<RefMut<A, C>, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.components
+            .get(handle.index.0)
+            .and_then(|slot| RefMut::new(slot.write_arc()))
+            .ok_or_else(|| unregistered::<C>(handle.id()))
+    }
+
     /// Iterate over the components.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics when it reaches a component which is mutably
+    /// borrowed through a typed [`RefMut`].
     pub fn iter(&self) -> Iter<'_, A> {
-        self.components.iter()
+        Iter(self.components.iter().zip(&self.metadata))
     }
 
     /// Iterate over the components mutably.
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics when it reaches a component which is borrowed
+    /// through a typed [`Ref`] or [`RefMut`].
     pub fn iter_mut(&mut self) -> IterMut<'_, A> {
-        self.components.iter_mut()
+        IterMut(self.components.iter_mut().zip(&self.metadata))
     }
 
-    /// Shutdown components (in the reverse order they were started)
+    /// Shutdown components (in the reverse order they were started).
+    ///
+    /// Fails when it reaches a component which is mutably borrowed through a
+    /// typed [`RefMut`].
     pub fn shutdown(&self, app: &A, shutdown: Shutdown) -> Result<(), FrameworkError> {
-        for component in self.components.iter().rev() {
-            component.before_shutdown(shutdown)?;
+        for (slot, metadata) in self.components.iter().zip(&self.metadata).rev() {
+            let component = slot.try_read().ok_or_else(|| {
+                format_err!(
+                    ComponentError,
+                    "can't shut down {} while it's mutably borrowed",
+                    metadata.id
+                )
+            })?;
+
+            self.timed(component.id(), Phase::BeforeShutdown, || {
+                component.before_shutdown(shutdown)
+            })?;
         }
 
         Ok(())
     }
 
-    /// Get a component reference by its type
-    pub fn get_downcast_ref<C>(&self) -> Option<&C>
+    /// Get the dependency graph of the registered components.
+    pub fn dependency_graph(&self) -> graph::Graph {
+        let nodes = self
+            .metadata
+            .iter()
+            .enumerate()
+            .map(|(order, metadata)| graph::Node {
+                id: metadata.id,
+                version: metadata.version.clone(),
+                order,
+            })
+            .collect();
+
+        let edges = self
+            .metadata
+            .iter()
+            .flat_map(|metadata| {
+                metadata.dependencies.iter().map(|dependency| graph::Edge {
+                    dependent: metadata.id,
+                    dependency: *dependency,
+                })
+            })
+            .collect();
+
+        graph::Graph::new(nodes, edges)
+    }
+
+    /// Get the lifecycle timings of the registered components.
+    pub fn timings(&self) -> timing::Report {
+        timing::Report::new(self.timings.lock().clone())
+    }
+
+    /// Check the health of all registered components.
+    ///
+    /// The health of components which are mutably borrowed through a typed
+    /// [`RefMut`] is reported as unknown.
+    pub fn health_report(&self) -> health::Report {
+        health::Report::new(
+            self.components
+                .iter()
+                .zip(&self.metadata)
+                .map(|(slot, metadata)| health::Entry {
+                    id: metadata.id,
+                    version: metadata.version.clone(),
+                    health: match slot.try_read() {
+                        Some(component) => component.health(),
+                        None => health::Health::Unknown("mutably borrowed".to_owned()),
+                    },
+                })
+                .collect(),
+        )
+    }
+
+    /// Get a component reference by its type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is mutably borrowed through a typed [`RefMut`].
+    pub fn get_downcast_ref<C>(&self) -> Option<MappedRwLockReadGuard<'_, C>>
     where
         C: Component<A>,
     {
         let index = *self.type_map.get(&TypeId::of::<C>())?;
-        self.components
-            .get(index.0)
-            .and_then(|box_component| (*(*box_component)).as_any().downcast_ref())
+        let guard = self.read(index)?;
+        ComponentReader::try_map(guard, |component| (**component).as_any().downcast_ref()).ok()
     }
 
-    /// Get a mutable component reference by its type
+    /// Get a mutable component reference by its type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is borrowed through a typed [`Ref`] or
+    /// [`RefMut`].
     pub fn get_downcast_mut<C>(&mut self) -> Option<&mut C>
     where
         C: Component<A>,
     {
         let index = *self.type_map.get(&TypeId::of::<C>())?;
-        self.components
-            .get_mut(index.0)
-            .and_then(|box_component| (*(*box_component)).as_mut_any().downcast_mut())
+        (**self.slot_mut(index)?).as_mut_any().downcast_mut()
+    }
+
+    /// Deliver a component's own configuration section (if present), then
+    /// fire its `after_config` callback.
+    fn configure(
+        &self,
+        component: &mut dyn Component<A>,
+        config: &A::Cfg,
+    ) -> Result<(), FrameworkError> {
+        self.timed(component.id(), Phase::AfterConfig, || {
+            if let Some(section) = component
+                .config_section()
+                .and_then(|name| self.config_sections.get(name))
+            {
+                component.load_config_section(section)?;
+            }
+
+            component.after_config(config)
+        })
+    }
+
+    /// Construct a boxed component, recording how long construction takes.
+    fn construct_boxed<F>(&self, f: F) -> Result<Box<dyn Component<A>>, FrameworkError>
+    where
+        F: FnOnce() -> Result<Box<dyn Component<A>>, FrameworkError>,
+    {
+        let span = debug_span!(
+            "component",
+            component = tracing::field::Empty,
+            phase = %Phase::Construct
+        );
+        let _enter = span.enter();
+
+        let started_at = Instant::now();
+        let component = f()?;
+        let duration = started_at.elapsed();
+
+        let id = component.id();
+        span.record("component", tracing::field::display(id));
+        debug!("{} took {:?}", Phase::Construct, duration);
+
+        self.record(Timing {
+            id,
+            phase: Phase::Construct,
+            duration,
+        });
+
+        Ok(component)
+    }
+
+    /// Run a lifecycle callback for a component, recording its timing.
+    fn timed<T>(&self, id: Id, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let (result, timing) = Timing::measure(id, phase, f);
+        self.record(timing);
+        result
+    }
+
+    /// Record a lifecycle timing, discarding the oldest one once
+    /// [`timing::MAX_TIMINGS`] have been recorded.
+    fn record(&self, timing: Timing) {
+        let mut timings = self.timings.lock();
+
+        if timings.len() >= timing::MAX_TIMINGS {
+            timings.remove(0);
+        }
+
+        timings.push(timing);
+    }
+
+    /// Get the index of the component of type `C`.
+    fn type_index<C>(&self) -> Result<Index, FrameworkError>
+    where
+        C: Component<A>,
+    {
+        self.type_map
+            .get(&TypeId::of::<C>())
+            .copied()
+            .ok_or_else(|| {
+                format_err!(
+                    ComponentError,
+                    "component not registered: {}",
+                    type_name::<C>()
+                )
+                .into()
+            })
     }
 
     /// Register an individual component.
@@ -207,7 +755,7 @@ where
     /// that particular context.
     fn register_component(
         &mut self,
-        component: Box<dyn Component<A>>,
+        mut component: Box<dyn Component<A>>,
     ) -> Result<(), FrameworkError> {
         let id = component.id();
         let version = component.version();
@@ -220,8 +768,12 @@ where
             id
         );
 
+        let subscriptions = component.subscribe(&self.events)?;
+        self.subscriptions.insert(id, subscriptions);
+
         let index = Index(self.components.len());
-        self.components.push(component);
+        self.metadata.push(Metadata::new(&*component));
+        self.components.push(Arc::new(RwLock::new(component)));
 
         // Index component by ID and type
         assert!(self.id_map.insert(id, index).is_none());
@@ -231,22 +783,108 @@ where
         Ok(())
     }
 
-    /// Borrow two components mutably (i.e. borrow splitting)
-    #[allow(clippy::type_complexity)]
+    /// Read the component at the given index without blocking, panicking if
+    /// it's mutably borrowed.
+    fn read(&self, index: Index) -> Option<ComponentReader<'_, A>> {
+        let slot = self.components.get(index.0)?;
+        Some(read(slot, &self.metadata[index.0]))
+    }
+
+    /// Get exclusive access to the component at the given index, panicking
+    /// if it's borrowed.
+    fn slot_mut(&mut self, index: Index) -> Option<&mut Box<dyn Component<A>>> {
+        let slot = self.components.get_mut(index.0)?;
+        Some(exclusive(slot, &self.metadata[index.0]))
+    }
+
+    /// Lock two (distinct) components mutably
     fn get2_mut(
-        &mut self,
+        &self,
         a: Index,
         b: Index,
-    ) -> (
-        Option<&mut Box<dyn Component<A>>>,
-        Option<&mut Box<dyn Component<A>>>,
-    ) {
-        let (a_slice, b_slice) = if b.0 <= self.components.len() {
-            self.components.split_at_mut(b.0)
-        } else {
-            (self.components.as_mut(), Default::default())
-        };
-
-        (a_slice.get_mut(a.0), b_slice.first_mut())
+    ) -> Result<(ComponentWriter<'_, A>, ComponentWriter<'_, A>), FrameworkError> {
+        ensure!(
+            a != b,
+            ComponentError,
+            "component can't depend on itself: {:?}",
+            a
+        );
+
+        match (self.components.get(a.0), self.components.get(b.0)) {
+            (Some(a), Some(b)) => Ok((a.write(), b.write())),
+            _ => fail!(
+                ComponentError,
+                "invalid component indexes: {:?}, {:?}",
+                a,
+                b
+            ),
+        }
     }
 }
+
+/// Iterator over the components in the registry.
+pub struct Iter<'a, A: Application + 'static>(
+    iter::Zip<slice::Iter<'a, Slot<A>>, slice::Iter<'a, Metadata>>,
+);
+
+impl<'a, A> Iterator for Iter<'a, A>
+where
+    A: Application + 'static,
+{
+    type Item = ComponentReader<'a, A>;
+
+    fn next(&mut self) -> Option<ComponentReader<'a, A>> {
+        self.0.next().map(|(slot, metadata)| read(slot, metadata))
+    }
+}
+
+/// Mutable iterator over the components in the registry.
+pub struct IterMut<'a, A: Application + 'static>(
+    iter::Zip<slice::IterMut<'a, Slot<A>>, slice::Iter<'a, Metadata>>,
+);
+
+impl<'a, A> Iterator for IterMut<'a, A>
+where
+    A: Application + 'static,
+{
+    type Item = &'a mut Box<dyn Component<A>>;
+
+    fn next(&mut self) -> Option<&'a mut Box<dyn Component<A>>> {
+        self.0
+            .next()
+            .map(|(slot, metadata)| exclusive(slot, metadata))
+    }
+}
+
+/// Read the given component without blocking, panicking if it's mutably
+/// borrowed (like [`RefCell::borrow`](std::cell::RefCell::borrow)).
+fn read<'a, A>(slot: &'a Slot<A>, metadata: &Metadata) -> ComponentReader<'a, A>
+where
+    A: Application + 'static,
+{
+    slot.try_read()
+        .unwrap_or_else(|| panic!("component already mutably borrowed: {}", metadata.id))
+}
+
+/// Get exclusive access to the given component, panicking if it's borrowed
+/// (like [`RefCell::borrow_mut`](std::cell::RefCell::borrow_mut)).
+fn exclusive<'a, A>(slot: &'a mut Slot<A>, metadata: &Metadata) -> &'a mut Box<dyn Component<A>>
+where
+    A: Application + 'static,
+{
+    match Arc::get_mut(slot) {
+        Some(component) => component.get_mut(),
+        None => panic!("component already borrowed: {}", metadata.id),
+    }
+}
+
+/// Error for when a component of type `C` isn't registered.
+fn unregistered<C>(id: Id) -> FrameworkError {
+    format_err!(
+        ComponentError,
+        "component not registered: {} ({})",
+        id,
+        type_name::<C>()
+    )
+    .into()
+}
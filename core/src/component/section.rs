@@ -0,0 +1,79 @@
+//! Per-component configuration sections.
+//!
+//! Components can declare a section of the application's configuration file
+//! which belongs to them, e.g. `[database]`, using the `config` attribute of
+//! the custom derive:
+//!
+//! ```
+//! use abscissa_core::{FrameworkError, component::{Component, ConfigSection}};
+//! use serde::Deserialize;
+//!
+//! #[derive(Clone, Debug, Default, Deserialize)]
+//! pub struct DatabaseConfig {
+//!     pub url: String,
+//! }
+//!
+//! #[derive(Component, Debug, Default)]
+//! #[component(config = "database")]
+//! pub struct DatabaseComponent {
+//!     config: DatabaseConfig,
+//! }
+//!
+//! impl ConfigSection for DatabaseComponent {
+//!     type Config = DatabaseConfig;
+//!
+//!     fn after_config_section(&mut self, config: DatabaseConfig) -> Result<(), FrameworkError> {
+//!         self.config = config;
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! When the application's configuration is loaded, the `[database]` table is
+//! deserialized as a `DatabaseConfig` and passed to the component, which
+//! decouples the component from the application's own configuration type
+//! and allows it to be shared between applications.
+//!
+//! Sections are delivered when the application's configuration is loaded
+//! (i.e. from the registry's `after_config`, which the application's own
+//! `after_config` invokes), immediately before the component's
+//! `after_config` callback. If the section is absent from the configuration
+//! file, the callback is not invoked and the component retains its existing
+//! configuration.
+//!
+//! Note that applications whose configuration types use
+//! `#[serde(deny_unknown_fields)]` will need to account for these sections.
+
+use crate::{FrameworkError, FrameworkErrorKind::ConfigError, config::Section};
+use serde::de::DeserializeOwned;
+
+/// Components which are configured by their own section of the application's
+/// configuration file.
+pub trait ConfigSection {
+    /// Type this component's configuration section is deserialized as.
+    type Config: DeserializeOwned;
+
+    /// Lifecycle event called with this component's section of the
+    /// configuration file after it has been loaded.
+    fn after_config_section(&mut self, config: Self::Config) -> Result<(), FrameworkError>;
+}
+
+/// Deserialize the given configuration section and deliver it to a component.
+///
+/// This is an internal function used by the custom derive.
+#[doc(hidden)]
+pub fn load<C>(component: &mut C, name: &str, section: &Section) -> Result<(), FrameworkError>
+where
+    C: ConfigSection,
+{
+    let config = section.clone().try_into().map_err(|e| {
+        format_err!(
+            ConfigError,
+            "error parsing `[{}]` configuration section: {}",
+            name,
+            e
+        )
+    })?;
+
+    component.after_config_section(config)
+}
@@ -0,0 +1,35 @@
+//! Plain-text tables used when displaying component reports.
+
+use std::{fmt, iter};
+
+/// Write the given rows as a table beneath the given headings, padding every
+/// column except the last one to the width of its widest cell.
+pub(super) fn write<const N: usize>(
+    f: &mut fmt::Formatter<'_>,
+    headings: [&str; N],
+    rows: impl IntoIterator<Item = [String; N]>,
+) -> fmt::Result {
+    let rows = iter::once(headings.map(String::from))
+        .chain(rows)
+        .collect::<Vec<_>>();
+
+    let mut widths = [0; N];
+
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    for row in &rows {
+        for (column, cell) in row.iter().enumerate() {
+            if column + 1 < N {
+                write!(f, "{:width$}  ", cell, width = widths[column])?;
+            } else {
+                writeln!(f, "{}", cell)?;
+            }
+        }
+    }
+
+    Ok(())
+}
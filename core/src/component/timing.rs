@@ -0,0 +1,190 @@
+//! Component lifecycle timings.
+//!
+//! The [`Registry`] records how long each component's lifecycle callbacks
+//! take to run, which is useful for determining which component is
+//! responsible for e.g. slow application startup.
+//!
+//! Each callback is run within a tracing span identifying the component and
+//! lifecycle phase. Setting the `ABSCISSA_TIMINGS=1` environment variable
+//! prints a report of the timings to standard error once the application
+//! has been initialized (and again for the shutdown phase).
+//!
+//! [`Registry`]: super::Registry
+
+use super::{id::Id, table};
+use crate::{FrameworkError, terminal};
+use std::{
+    env,
+    fmt::{self, Display},
+    io::Write,
+    slice,
+    time::{Duration, Instant},
+};
+
+/// Environment variable which enables printing timing reports.
+pub const ENV_VAR: &str = "ABSCISSA_TIMINGS";
+
+/// Maximum number of timings retained by a [`Registry`], after which the
+/// oldest are discarded (e.g. when components are repeatedly restarted).
+///
+/// [`Registry`]: super::Registry
+pub const MAX_TIMINGS: usize = 1024;
+
+/// Column headings used when displaying a [`Report`] as a table.
+const TABLE_HEADINGS: [&str; 3] = ["COMPONENT", "PHASE", "DURATION"];
+
+/// Is printing timing reports enabled via the `ABSCISSA_TIMINGS`
+/// environment variable?
+pub fn enabled() -> bool {
+    env::var_os(ENV_VAR).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Phases of the component lifecycle which are timed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Phase {
+    /// Constructing the component (see `Registry::construct`)
+    Construct,
+
+    /// `after_config` callback (including delivering its config section)
+    AfterConfig,
+
+    /// `register_dependency` callback for the given dependency
+    RegisterDependency(Id),
+
+    /// `before_shutdown` callback
+    BeforeShutdown,
+}
+
+impl Phase {
+    /// Is this phase part of application startup?
+    pub fn is_startup(self) -> bool {
+        self != Phase::BeforeShutdown
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Construct => f.write_str("construct"),
+            Phase::AfterConfig => f.write_str("after_config"),
+            Phase::RegisterDependency(id) => write!(f, "register_dependency({})", id),
+            Phase::BeforeShutdown => f.write_str("before_shutdown"),
+        }
+    }
+}
+
+/// Wall-clock duration of a lifecycle phase of a particular component.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Timing {
+    /// Component identifier
+    pub id: Id,
+
+    /// Lifecycle phase
+    pub phase: Phase,
+
+    /// How long the phase took
+    pub duration: Duration,
+}
+
+impl Timing {
+    /// Run the given lifecycle callback within a tracing span, measuring how
+    /// long it takes.
+    pub(crate) fn measure<T>(id: Id, phase: Phase, f: impl FnOnce() -> T) -> (T, Self) {
+        let span = debug_span!("component", component = %id, phase = %phase);
+        let _enter = span.enter();
+
+        let started_at = Instant::now();
+        let result = f();
+
+        let timing = Self {
+            id,
+            phase,
+            duration: started_at.elapsed(),
+        };
+
+        debug!("{} took {:?}", phase, timing.duration);
+        (result, timing)
+    }
+}
+
+/// Report of the lifecycle timings of all components in a registry.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    /// Timings in the order they were recorded
+    timings: Vec<Timing>,
+}
+
+impl Report {
+    /// Create a new report from the given timings.
+    pub fn new(timings: Vec<Timing>) -> Self {
+        Self { timings }
+    }
+
+    /// Timings of the phases which are part of application startup.
+    pub fn startup(&self) -> Report {
+        self.filter(|timing| timing.phase.is_startup())
+    }
+
+    /// Timings of the phases which are part of application shutdown.
+    pub fn shutdown(&self) -> Report {
+        self.filter(|timing| !timing.phase.is_startup())
+    }
+
+    /// Timings of the given component.
+    pub fn component(&self, id: Id) -> Report {
+        self.filter(|timing| timing.id == id)
+    }
+
+    /// Total duration of all timings in this report.
+    pub fn total(&self) -> Duration {
+        self.timings.iter().map(|timing| timing.duration).sum()
+    }
+
+    /// Iterate over the timings in this report.
+    pub fn iter(&self) -> slice::Iter<'_, Timing> {
+        self.timings.iter()
+    }
+
+    /// Is this report empty?
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_empty()
+    }
+
+    /// Print this report to the terminal's standard error.
+    pub fn print(&self) -> Result<(), FrameworkError> {
+        let mut stderr = terminal::stderr().lock();
+        write!(stderr, "{}", self)?;
+        stderr.flush()?;
+        Ok(())
+    }
+
+    /// Create a new report containing the timings which match a predicate.
+    fn filter(&self, predicate: impl Fn(&Timing) -> bool) -> Report {
+        Report::new(self.iter().copied().filter(predicate).collect())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self.timings.iter().map(|timing| {
+            [
+                timing.id.to_string(),
+                timing.phase.to_string(),
+                format!("{:?}", timing.duration),
+            ]
+        });
+
+        table::write(f, TABLE_HEADINGS, rows)?;
+        writeln!(f)?;
+        writeln!(f, "total: {:?}", self.total())
+    }
+}
+
+impl<'a> IntoIterator for &'a Report {
+    type Item = &'a Timing;
+    type IntoIter = slice::Iter<'a, Timing>;
+
+    fn into_iter(self) -> slice::Iter<'a, Timing> {
+        self.iter()
+    }
+}
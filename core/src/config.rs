@@ -3,8 +3,14 @@
 mod cell;
 mod configurable;
 mod overrides;
+mod sections;
 
-pub use self::{cell::CfgCell, configurable::Configurable, overrides::Override};
+pub use self::{
+    cell::CfgCell,
+    configurable::Configurable,
+    overrides::Override,
+    sections::{Section, Sections},
+};
 
 use crate::{
     FrameworkError,
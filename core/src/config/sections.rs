@@ -0,0 +1,184 @@
+//! Raw (untyped) configuration file sections.
+//!
+//! The sections named in [`RESERVED`] configure the framework itself (e.g.
+//! `[components]` and `[tracing]`), and are excluded when deserializing the
+//! application's own configuration. Application configuration types which
+//! have fields with these names still load, but the fields are never set:
+//! configuring one of them in the file is reported as an error, rather than
+//! it being silently ignored.
+
+use crate::{
+    FrameworkError,
+    FrameworkErrorKind::{ConfigError, IoError, ParseError, PathError},
+    error::BoxError,
+    fs::File,
+    path::AbsPath,
+};
+use serde::{
+    Deserializer,
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any,
+};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Names of the sections reserved for configuring the framework itself.
+pub const RESERVED: &[&str] = &["components", "tracing"];
+
+/// Raw value of an individual configuration file section.
+pub type Section = toml::Value;
+
+/// Raw (untyped) sections of a configuration file, keyed by their names.
+///
+/// These are used to deliver individual sections of the configuration file
+/// to the components which declare them, independent of the application's
+/// own configuration type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sections {
+    /// Parsed configuration file
+    table: toml::Table,
+
+    /// Path to the configuration file (if loaded from one)
+    path: Option<PathBuf>,
+}
+
+impl Sections {
+    /// Parse configuration sections from the given TOML string.
+    pub fn load_toml(toml_string: impl AsRef<str>) -> Result<Self, FrameworkError> {
+        Ok(toml::from_str::<toml::Table>(toml_string.as_ref())?.into())
+    }
+
+    /// Load configuration sections from the TOML file at the given path.
+    pub fn load_toml_file(path: impl AsRef<AbsPath>) -> Result<Self, FrameworkError> {
+        let path = path.as_ref().as_path();
+        let mut file = File::open(path).map_err(|e| path_error(path, IoError.context(e)))?;
+
+        let mut toml_string = String::new();
+        file.read_to_string(&mut toml_string)?;
+
+        let table =
+            toml::from_str(&toml_string).map_err(|e| path_error(path, ParseError.context(e)))?;
+
+        Ok(Self {
+            table,
+            path: Some(path.to_owned()),
+        })
+    }
+
+    /// Deserialize the application's own configuration from these sections,
+    /// excluding the sections reserved by the framework.
+    ///
+    /// Returns an error if a reserved section is present and the
+    /// configuration type also has a field named after it.
+    pub fn application_config<C>(&self) -> Result<C, FrameworkError>
+    where
+        C: DeserializeOwned,
+    {
+        if let Some(name) = struct_fields::<C>()
+            .iter()
+            .find(|field| RESERVED.contains(field) && self.contains(field))
+        {
+            fail!(
+                ConfigError,
+                "configuration field `{}` conflicts with the framework's reserved `[{}]` section",
+                name,
+                name
+            );
+        }
+
+        let mut table = self.table.clone();
+
+        for name in RESERVED {
+            table.remove(*name);
+        }
+
+        Section::Table(table)
+            .try_into()
+            .map_err(|e| match &self.path {
+                Some(path) => path_error(path, ParseError.context(e)),
+                None => ParseError.context(e).into(),
+            })
+    }
+
+    /// Get the section with the given name.
+    pub fn get(&self, name: &str) -> Option<&Section> {
+        self.table.get(name)
+    }
+
+    /// Does the configuration contain a section with the given name?
+    pub fn contains(&self, name: &str) -> bool {
+        self.table.contains_key(name)
+    }
+
+    /// Is the configuration empty?
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Get the path to the configuration file these sections were loaded
+    /// from (if any).
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+impl From<toml::Table> for Sections {
+    fn from(table: toml::Table) -> Sections {
+        Sections { table, path: None }
+    }
+}
+
+/// Create an error for the configuration file at the given path.
+fn path_error(path: &Path, err: impl Into<BoxError>) -> FrameworkError {
+    let path_error = PathError {
+        name: Some(path.into()),
+    }
+    .context(err);
+    ConfigError.context(path_error).into()
+}
+
+/// Get the names of the fields of `C`, if it's deserialized as a struct.
+fn struct_fields<C>() -> &'static [&'static str]
+where
+    C: DeserializeOwned,
+{
+    let mut fields = &[][..];
+    let _ = C::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Deserializer which records the field names of the struct being
+/// deserialized (and deserializes nothing).
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.0 = fields;
+        Err(de::Error::custom("field names recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
@@ -66,6 +66,10 @@ pub enum FrameworkErrorKind {
     /// Errors involving multithreading
     ThreadError,
 
+    /// Terminal is unavailable, e.g. prompting when stdin isn't a TTY
+    #[cfg(feature = "terminal")]
+    TerminalError,
+
     /// Timeout performing operation
     TimeoutError,
 }
@@ -87,6 +91,8 @@ impl FrameworkErrorKind {
             FrameworkErrorKind::PathError { .. } => "path error",
             FrameworkErrorKind::ProcessError => "subprocess error",
             FrameworkErrorKind::ThreadError => "thread error",
+            #[cfg(feature = "terminal")]
+            FrameworkErrorKind::TerminalError => "terminal error",
             FrameworkErrorKind::TimeoutError => "operation timed out",
         }
     }
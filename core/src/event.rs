@@ -0,0 +1,239 @@
+//! Inter-component event bus.
+//!
+//! The event bus provides typed publish/subscribe messaging between
+//! components (or any other application code) without coupling them via
+//! [`Handle`]s or taking a lock on the component [`Registry`].
+//!
+//! Events are values of any `Send + Sync + 'static` type. Subscribers are
+//! keyed by the event's type, and can receive events either synchronously
+//! (the handler runs on the publishing thread) or by way of a channel, e.g.
+//! to a thread spawned by the [`thread::Manager`].
+//!
+//! ```
+//! use abscissa_core::event::Bus;
+//! use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+//!
+//! /// Event published when a user logs in
+//! #[derive(Clone, Debug)]
+//! pub struct LoggedIn {
+//!     pub username: String,
+//! }
+//!
+//! let bus = Bus::default();
+//! let logins = Arc::new(AtomicUsize::new(0));
+//!
+//! let counter = Arc::clone(&logins);
+//! bus.subscribe(move |_event: &LoggedIn| {
+//!     counter.fetch_add(1, Ordering::SeqCst);
+//! });
+//!
+//! bus.publish(LoggedIn { username: "alice".to_owned() });
+//! assert_eq!(logins.load(Ordering::SeqCst), 1);
+//! ```
+//!
+//! Within an application, the bus is accessible via the application state,
+//! i.e. `APP.state().events()`. Components can subscribe to events when they
+//! are registered using the [`Component::subscribe`] callback.
+//!
+//! [`Handle`]: crate::component::Handle
+//! [`Registry`]: crate::component::Registry
+//! [`Component::subscribe`]: crate::component::Component::subscribe
+
+use crate::{FrameworkError, Map, thread};
+use crossbeam_channel::{Receiver, select};
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Error message to use for lock error panics.
+const LOCK_ERR_MSG: &str = "error acquiring event bus lock";
+
+/// Events which can be published on the [`Bus`].
+///
+/// This trait is automatically impl'd for all `Send + Sync + 'static` types.
+pub trait Event: Any + Send + Sync {}
+
+impl<T: Any + Send + Sync> Event for T {}
+
+/// Type-erased event handler, which returns `false` if the subscription
+/// should be removed (e.g. because the receiving end of a channel is gone).
+type Handler = Arc<dyn Fn(&dyn Any) -> bool + Send + Sync>;
+
+/// Handle to a subscription, which can be used to unsubscribe.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Subscription {
+    /// Type of event subscribed to
+    type_id: TypeId,
+
+    /// Unique identifier for this subscription
+    id: u64,
+}
+
+/// Event bus: delivers published events to their subscribers.
+#[derive(Default)]
+pub struct Bus {
+    /// Handlers for each type of event
+    handlers: RwLock<Map<TypeId, Vec<(u64, Handler)>>>,
+
+    /// Counter used to allocate subscription IDs
+    next_id: AtomicU64,
+}
+
+impl Bus {
+    /// Subscribe to events of type `E`, invoking the given handler
+    /// synchronously on the publishing thread.
+    pub fn subscribe<E, F>(&self, handler: F) -> Subscription
+    where
+        E: Event,
+        F: Fn(&E) + Send + Sync + 'static,
+    {
+        self.add_handler::<E>(Arc::new(move |event| {
+            if let Some(event) = event.downcast_ref::<E>() {
+                handler(event);
+            }
+
+            true
+        }))
+    }
+
+    /// Subscribe to events of type `E`, delivering them to the returned
+    /// channel (e.g. for consumption by another thread).
+    ///
+    /// The subscription is removed once the receiver has been dropped.
+    pub fn channel<E>(&self) -> (Subscription, Receiver<E>)
+    where
+        E: Event + Clone,
+    {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        let subscription = self.add_handler::<E>(Arc::new(move |event| {
+            event
+                .downcast_ref::<E>()
+                .is_none_or(|event| sender.send(event.clone()).is_ok())
+        }));
+
+        (subscription, receiver)
+    }
+
+    /// Spawn a thread within the given thread manager which invokes the
+    /// given handler for each published event of type `E`.
+    ///
+    /// The thread exits when termination is requested by the thread manager.
+    pub fn spawn_subscriber<E, F>(
+        &self,
+        threads: &mut thread::Manager,
+        name: impl TryInto<thread::Name>,
+        mut handler: F,
+    ) -> Result<Subscription, FrameworkError>
+    where
+        E: Event + Clone,
+        F: FnMut(E) + Send + 'static,
+    {
+        let (subscription, receiver) = self.channel::<E>();
+
+        let result = threads.spawn(name, move || {
+            let terminate = thread::termination_receiver();
+
+            loop {
+                select! {
+                    recv(receiver) -> event => match event {
+                        Ok(event) => handler(event),
+                        Err(_) => break,
+                    },
+                    recv(terminate) -> _ => break,
+                }
+            }
+        });
+
+        if let Err(e) = result {
+            self.unsubscribe(subscription);
+            return Err(e);
+        }
+
+        Ok(subscription)
+    }
+
+    /// Remove a subscription. Returns `true` if it was previously subscribed.
+    pub fn unsubscribe(&self, subscription: Subscription) -> bool {
+        let mut handlers = self.handlers.write().expect(LOCK_ERR_MSG);
+
+        let Some(subscribers) = handlers.get_mut(&subscription.type_id) else {
+            return false;
+        };
+
+        let len = subscribers.len();
+        subscribers.retain(|(id, _)| *id != subscription.id);
+        subscribers.len() != len
+    }
+
+    /// Publish an event, delivering it to all subscribers of its type.
+    ///
+    /// Handlers are invoked on the calling thread without holding any locks,
+    /// so they may themselves publish events or manage subscriptions.
+    ///
+    /// Returns the number of subscribers the event was delivered to.
+    pub fn publish<E: Event>(&self, event: E) -> usize {
+        let type_id = TypeId::of::<E>();
+
+        let subscribers = match self.handlers.read().expect(LOCK_ERR_MSG).get(&type_id) {
+            Some(subscribers) => subscribers.clone(),
+            None => return 0,
+        };
+
+        let mut delivered = 0;
+
+        for (id, handler) in subscribers {
+            if handler(&event) {
+                delivered += 1;
+            } else {
+                self.unsubscribe(Subscription { type_id, id });
+            }
+        }
+
+        delivered
+    }
+
+    /// Get the number of subscribers to events of type `E`.
+    pub fn subscribers<E: Event>(&self) -> usize {
+        self.handlers
+            .read()
+            .expect(LOCK_ERR_MSG)
+            .get(&TypeId::of::<E>())
+            .map(Vec::len)
+            .unwrap_or_default()
+    }
+
+    /// Add a type-erased handler for events of type `E`.
+    fn add_handler<E: Event>(&self, handler: Handler) -> Subscription {
+        let type_id = TypeId::of::<E>();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        self.handlers
+            .write()
+            .expect(LOCK_ERR_MSG)
+            .entry(type_id)
+            .or_default()
+            .push((id, handler));
+
+        Subscription { type_id, id }
+    }
+}
+
+impl fmt::Debug for Bus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subscribers = self
+            .handlers
+            .read()
+            .map(|handlers| handlers.values().map(Vec::len).sum::<usize>())
+            .unwrap_or_default();
+
+        f.debug_struct("Bus")
+            .field("subscribers", &subscribers)
+            .finish_non_exhaustive()
+    }
+}
@@ -36,11 +36,14 @@ pub mod command;
 pub mod component;
 #[cfg(feature = "config")]
 pub mod config;
+#[cfg(feature = "application")]
+pub mod event;
 pub mod path;
 #[cfg(feature = "application")]
 pub mod prelude;
 mod runnable;
 #[cfg(feature = "application")]
+pub mod scheduler;
 mod shutdown;
 #[cfg(feature = "testing")]
 pub mod testing;
@@ -53,6 +56,7 @@ pub mod trace;
 pub use crate::{
     error::framework::{FrameworkError, FrameworkErrorKind},
     runnable::{Runnable, RunnableMut},
+    shutdown::Shutdown,
 };
 pub use std::collections::{BTreeMap as Map, btree_map as map, btree_set as set};
 
@@ -60,7 +64,6 @@ pub use std::collections::{BTreeMap as Map, btree_map as map, btree_set as set};
 pub use crate::{
     application::{Application, boot},
     component::{Component, Injectable},
-    shutdown::Shutdown,
 };
 
 #[cfg(feature = "config")]
@@ -73,6 +76,8 @@ pub use crate::{command::Command, path::StandardPaths};
 
 #[cfg(feature = "options")]
 pub use clap;
+#[cfg(feature = "application")]
+pub use crossbeam_channel;
 pub use fs_err as fs;
 #[cfg(feature = "secrets")]
 pub use secrecy as secret;
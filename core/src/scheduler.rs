@@ -0,0 +1,276 @@
+//! Scheduler component: runs named jobs periodically or on a cron schedule.
+//!
+//! Each job is run on its own thread within the application's thread
+//! manager, named `scheduler/<job name>`, which exits when the thread manager
+//! requests that it terminate (e.g. when the application shuts down).
+//!
+//! ```ignore
+//! use abscissa_core::scheduler::{Job, Schedule, Scheduler};
+//! use std::time::Duration;
+//!
+//! let state = APP.state();
+//! let mut scheduler = state.component_mut::<Scheduler>()?;
+//! let mut threads = state.threads_mut();
+//!
+//! scheduler.schedule(
+//!     &mut threads,
+//!     Job::new("heartbeat", Schedule::every(Duration::from_secs(30)), || {
+//!         send_heartbeat();
+//!     })
+//!     .with_jitter(Duration::from_secs(5)),
+//! )?;
+//!
+//! scheduler.schedule(
+//!     &mut threads,
+//!     Job::new("cleanup", Schedule::cron("0 2 * * *")?, || {
+//!         delete_stale_files();
+//!     }),
+//! )?;
+//! ```
+//!
+//! The scheduler uses a pluggable [`Clock`], allowing tests to use a
+//! [`ManualClock`] to advance time deterministically.
+
+mod clock;
+mod cron;
+
+pub use self::{
+    clock::{Clock, ManualClock, SystemClock},
+    cron::Cron,
+};
+
+use crate::{Component, FrameworkError, FrameworkErrorKind::ThreadError, thread};
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+/// Prefix of the names of the threads which run scheduled jobs.
+pub const THREAD_PREFIX: &str = "scheduler";
+
+/// When a job runs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Schedule {
+    /// Run repeatedly with the given interval between scheduled runs
+    Interval(Duration),
+
+    /// Run at the times matching a cron expression (in UTC)
+    Cron(Cron),
+}
+
+impl Schedule {
+    /// Run repeatedly with the given interval between scheduled runs.
+    pub fn every(interval: Duration) -> Self {
+        Schedule::Interval(interval)
+    }
+
+    /// Run at the times matching the given cron expression (in UTC).
+    pub fn cron(expr: &str) -> Result<Self, FrameworkError> {
+        expr.parse().map(Schedule::Cron)
+    }
+
+    /// Get the next scheduled time after the given time (if any).
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        match self {
+            Schedule::Interval(interval) => time.checked_add(*interval),
+            Schedule::Cron(cron) => cron.next_after(time),
+        }
+    }
+}
+
+/// What to do when a job is still running at its next scheduled time.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Overlap {
+    /// Skip the missed runs, waiting for the next scheduled time
+    #[default]
+    Skip,
+
+    /// Run the job again as soon as the current run completes (at most one
+    /// run is queued, regardless of how many were missed)
+    Queue,
+}
+
+/// Named job run by the [`Scheduler`].
+pub struct Job {
+    /// Name of the job
+    name: String,
+
+    /// When the job runs
+    schedule: Schedule,
+
+    /// Maximum random delay added to each scheduled run
+    jitter: Duration,
+
+    /// What to do when the job is still running at its next scheduled time
+    overlap: Overlap,
+
+    /// Task to run
+    task: Box<dyn FnMut() + Send>,
+}
+
+impl Job {
+    /// Create a new job which runs the given task on the given schedule.
+    pub fn new<F>(name: impl Into<String>, schedule: Schedule, task: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            schedule,
+            jitter: Duration::ZERO,
+            overlap: Overlap::default(),
+            task: Box::new(task),
+        }
+    }
+
+    /// Delay each run by a random amount of time up to the given maximum,
+    /// e.g. to avoid many instances of an application running a job at once.
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set what to do when the job is still running at its next scheduled
+    /// time.
+    pub fn with_overlap(mut self, overlap: Overlap) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Get the name of this job.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the schedule of this job.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Run this job until its schedule ends or the current thread is
+    /// requested to terminate, starting at the given time.
+    fn run(mut self, clock: &dyn Clock, mut scheduled: SystemTime) {
+        loop {
+            if !clock.sleep_until(scheduled + random_delay(self.jitter)) {
+                return;
+            }
+
+            // Panics are reported by the panic hook, but don't stop the job
+            let _ = panic::catch_unwind(AssertUnwindSafe(&mut self.task));
+
+            let Some(next) = self.schedule.next_after(scheduled) else {
+                return;
+            };
+
+            let now = clock.now();
+
+            scheduled = if next >= now {
+                next
+            } else {
+                match self.overlap {
+                    Overlap::Skip => match self.schedule.next_after(now) {
+                        Some(next) => next,
+                        None => return,
+                    },
+                    Overlap::Queue => now,
+                }
+            };
+        }
+    }
+}
+
+impl fmt::Debug for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job")
+            .field("name", &self.name)
+            .field("schedule", &self.schedule)
+            .field("jitter", &self.jitter)
+            .field("overlap", &self.overlap)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Scheduler component.
+#[derive(Component, Debug)]
+#[component(core)]
+pub struct Scheduler {
+    /// Clock used to determine when jobs run
+    clock: Arc<dyn Clock>,
+
+    /// Names of the jobs which have been scheduled
+    jobs: Vec<String>,
+}
+
+impl Scheduler {
+    /// Create a new scheduler which uses the system clock.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+
+    /// Create a new scheduler which uses the given clock.
+    pub fn with_clock(clock: impl Clock) -> Self {
+        Self {
+            clock: Arc::new(clock),
+            jobs: vec![],
+        }
+    }
+
+    /// Get the clock used by this scheduler.
+    pub fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    /// Get the names of the jobs which have been scheduled.
+    pub fn jobs(&self) -> impl Iterator<Item = &str> {
+        self.jobs.iter().map(String::as_str)
+    }
+
+    /// Schedule the given job, spawning a thread to run it within the given
+    /// thread manager.
+    pub fn schedule(
+        &mut self,
+        threads: &mut thread::Manager,
+        job: Job,
+    ) -> Result<(), FrameworkError> {
+        if job.schedule == Schedule::Interval(Duration::ZERO) {
+            fail!(ThreadError, "job '{}' has a zero interval", job.name);
+        }
+
+        let name = thread::Name::new(format!("{}/{}", THREAD_PREFIX, job.name))?;
+        let job_name = job.name.clone();
+        let clock = Arc::clone(&self.clock);
+
+        // Compute the first run before spawning the thread so it isn't
+        // affected by how long the thread takes to start
+        let Some(scheduled) = job.schedule.next_after(clock.now()) else {
+            fail!(ThreadError, "job '{}' is never scheduled to run", job.name);
+        };
+
+        threads.spawn(name, move || job.run(&*clock, scheduled))?;
+        self.jobs.push(job_name);
+        Ok(())
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get a random delay up to the given maximum.
+fn random_delay(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+
+    // Hashing with randomly keyed SipHash provides a source of randomness
+    // without requiring an additional dependency
+    let random = RandomState::new().build_hasher().finish();
+    let max_nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    Duration::from_nanos(random % max_nanos.saturating_add(1))
+}
@@ -0,0 +1,155 @@
+//! Clocks used by the scheduler.
+
+use crate::thread;
+use crossbeam_channel::{Sender, select};
+use std::{
+    fmt,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, SystemTime},
+};
+
+/// Source of time for the [`Scheduler`].
+///
+/// [`Scheduler`]: super::Scheduler
+pub trait Clock: fmt::Debug + Send + Sync + 'static {
+    /// Get the current time.
+    fn now(&self) -> SystemTime;
+
+    /// Block the current thread until the given deadline, returning `false`
+    /// if the thread is requested to terminate first.
+    ///
+    /// Must be called from a thread spawned by the thread manager.
+    fn sleep_until(&self, deadline: SystemTime) -> bool;
+}
+
+/// Clock which uses the system's wall-clock time.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep_until(&self, deadline: SystemTime) -> bool {
+        // Check the time after each sleep in case the system clock changes
+        while let Ok(remaining) = deadline.duration_since(SystemTime::now()) {
+            if remaining.is_zero() {
+                break;
+            }
+
+            if !thread::sleep_or_terminate(remaining) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Clock which only advances when told to, for deterministic tests.
+///
+/// Clones of a manual clock share the same time.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    /// Shared state of the clock
+    state: Arc<Mutex<ManualState>>,
+}
+
+/// State of a [`ManualClock`].
+#[derive(Debug)]
+struct ManualState {
+    /// Current time
+    now: SystemTime,
+
+    /// Deadlines of the threads sleeping on this clock, along with senders
+    /// used to wake them
+    sleepers: Vec<(SystemTime, Sender<()>)>,
+}
+
+impl ManualClock {
+    /// Create a new manual clock set to the given time.
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ManualState {
+                now,
+                sleepers: vec![],
+            })),
+        }
+    }
+
+    /// Advance the clock by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        let now = self.now() + duration;
+        self.set(now);
+    }
+
+    /// Set the clock to the given time, waking any threads whose deadline
+    /// has passed.
+    pub fn set(&self, now: SystemTime) {
+        let mut state = self.lock();
+        state.now = now;
+
+        state.sleepers.retain(|(deadline, waker)| {
+            if *deadline <= now {
+                let _ = waker.try_send(());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Get the deadlines of the threads currently sleeping on this clock.
+    pub fn deadlines(&self) -> Vec<SystemTime> {
+        self.lock()
+            .sleepers
+            .iter()
+            .map(|(deadline, _)| *deadline)
+            .collect()
+    }
+
+    /// Lock the state of this clock, ignoring poisoning.
+    fn lock(&self) -> MutexGuard<'_, ManualState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ManualClock {
+    /// Starts at the current system time.
+    fn default() -> Self {
+        Self::new(SystemTime::now())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        self.lock().now
+    }
+
+    fn sleep_until(&self, deadline: SystemTime) -> bool {
+        let terminate = thread::termination_receiver();
+
+        loop {
+            let (waker, woken) = crossbeam_channel::bounded(1);
+
+            {
+                let mut state = self.lock();
+
+                if state.now >= deadline {
+                    return true;
+                }
+
+                state.sleepers.push((deadline, waker.clone()));
+            }
+
+            select! {
+                recv(woken) -> _ => (),
+                recv(terminate) -> _ => {
+                    self.lock().sleepers.retain(|(_, w)| !w.same_channel(&waker));
+                    return false;
+                }
+            }
+        }
+    }
+}
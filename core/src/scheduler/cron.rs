@@ -0,0 +1,388 @@
+//! Cron expressions.
+
+use crate::{FrameworkError, FrameworkErrorKind::ParseError};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Minutes per day.
+const MINUTES_PER_DAY: u64 = 24 * 60;
+
+/// Maximum number of minutes to search for the next occurrence of a cron
+/// expression before concluding that it will never occur (about 8 years,
+/// which covers expressions which only match on leap days: these can be
+/// 8 years apart, e.g. 2096-02-29 and 2104-02-29).
+const MAX_SEARCH_MINUTES: u64 = 8 * 366 * MINUTES_PER_DAY;
+
+/// Cron expression with the standard 5 fields, evaluated in UTC:
+///
+/// ```text
+/// ┌───────────── minute (0-59)
+/// │ ┌─────────── hour (0-23)
+/// │ │ ┌───────── day of month (1-31)
+/// │ │ │ ┌─────── month (1-12)
+/// │ │ │ │ ┌───── day of week (0-7, where both 0 and 7 are Sunday)
+/// │ │ │ │ │
+/// 0 2 * * *
+/// ```
+///
+/// Each field is a comma-separated list of `*`, values (`5`), or ranges
+/// (`1-5`), each of which can have a step (`*/15`, `0-30/10`).
+///
+/// As in traditional cron, if both the day of month and day of week fields
+/// are restricted (i.e. don't match every day), times matching either of
+/// them match.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cron {
+    /// Original expression
+    expr: String,
+
+    /// Bitmask of matching minutes
+    minutes: u64,
+
+    /// Bitmask of matching hours
+    hours: u64,
+
+    /// Bitmask of matching days of the month
+    days: u64,
+
+    /// Bitmask of matching months
+    months: u64,
+
+    /// Bitmask of matching days of the week (0 = Sunday)
+    weekdays: u64,
+
+    /// Is the day of month field restricted?
+    days_restricted: bool,
+
+    /// Is the day of week field restricted?
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    /// Get the next time after the given time that matches this expression.
+    ///
+    /// Returns `None` if the expression never matches (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut minute = elapsed.as_secs() / 60 + 1;
+        let limit = minute + MAX_SEARCH_MINUTES;
+
+        while minute < limit {
+            let days = minute / MINUTES_PER_DAY;
+            let (year, month, day) = civil_from_days(days);
+
+            if !matches(self.months, month) {
+                minute = first_day_of_next_month(year, month) * MINUTES_PER_DAY;
+                continue;
+            }
+
+            if !self.day_matches(day, (days + 4) % 7) {
+                minute = (days + 1) * MINUTES_PER_DAY;
+                continue;
+            }
+
+            if !matches(self.hours, minute % MINUTES_PER_DAY / 60) {
+                minute = (minute / 60 + 1) * 60;
+                continue;
+            }
+
+            if !matches(self.minutes, minute % 60) {
+                minute += 1;
+                continue;
+            }
+
+            return Some(UNIX_EPOCH + Duration::from_secs(minute * 60));
+        }
+
+        None
+    }
+
+    /// Does the given day of the month/week match this expression?
+    fn day_matches(&self, day: u64, weekday: u64) -> bool {
+        let day_matches = matches(self.days, day);
+        let weekday_matches = matches(self.weekdays, weekday);
+
+        if self.days_restricted && self.weekdays_restricted {
+            day_matches || weekday_matches
+        } else {
+            day_matches && weekday_matches
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = FrameworkError;
+
+    fn from_str(expr: &str) -> Result<Self, FrameworkError> {
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            fail!(
+                ParseError,
+                "cron expression must have 5 fields (got {}): {:?}",
+                fields.len(),
+                expr
+            );
+        };
+
+        let days = parse_field(days, 1, 31)?;
+        let mut weekdays = parse_field(weekdays, 0, 7)?;
+
+        // Both 0 and 7 are Sunday
+        if matches(weekdays, 7) {
+            weekdays |= 1;
+        }
+
+        Ok(Self {
+            expr: fields.join(" "),
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days,
+            months: parse_field(months, 1, 12)?,
+            weekdays,
+            days_restricted: !covers(days, 1, 31),
+            weekdays_restricted: !covers(weekdays, 0, 6),
+        })
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+/// Parse a field of a cron expression into a bitmask of the values it
+/// matches, which must be within the given (inclusive) range.
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, FrameworkError> {
+    let mut mask = 0;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, parse_value(step, 1, max)?),
+            None => (item, 1),
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (parse_value(start, min, max)?, parse_value(end, min, max)?),
+            None if step > 1 => (parse_value(range, min, max)?, max),
+            None => {
+                let value = parse_value(range, min, max)?;
+                (value, value)
+            }
+        };
+
+        ensure!(start <= end, ParseError, "invalid cron range: {:?}", range);
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+/// Parse a value in a cron expression, which must be within the given
+/// (inclusive) range.
+fn parse_value(value: &str, min: u64, max: u64) -> Result<u64, FrameworkError> {
+    let parsed = value
+        .parse::<u64>()
+        .map_err(|_| format_err!(ParseError, "invalid cron value: {:?}", value))?;
+
+    ensure!(
+        (min..=max).contains(&parsed),
+        ParseError,
+        "cron value {} out of range ({}-{})",
+        parsed,
+        min,
+        max
+    );
+
+    Ok(parsed)
+}
+
+/// Does the given bitmask contain the given value?
+fn matches(mask: u64, value: u64) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Does the given bitmask contain every value in the given (inclusive) range?
+fn covers(mask: u64, min: u64, max: u64) -> bool {
+    (min..=max).all(|value| matches(mask, value))
+}
+
+/// Convert days since the Unix epoch into a `(year, month, day)` date.
+///
+/// See: <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Convert a `(year, month, day)` date into days since the Unix epoch.
+///
+/// See: <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Get the first day of the month after the given one, in days since the
+/// Unix epoch.
+fn first_day_of_next_month(year: u64, month: u64) -> u64 {
+    if month == 12 {
+        days_from_civil(year + 1, 1, 1)
+    } else {
+        days_from_civil(year, month + 1, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cron;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// 2024-01-01T00:00:00Z (a Monday)
+    const JAN_1_2024: u64 = 1_704_067_200;
+
+    /// Get the time the given number of seconds after the Unix epoch
+    fn time(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn daily() {
+        let cron = "0 2 * * *".parse::<Cron>().unwrap();
+
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024)),
+            Some(time(JAN_1_2024 + 2 * 3600))
+        );
+
+        // Strictly after the given time
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024 + 2 * 3600)),
+            Some(time(JAN_1_2024 + 86400 + 2 * 3600))
+        );
+    }
+
+    #[test]
+    fn steps_and_lists() {
+        let cron = "*/15 9,17 * * *".parse::<Cron>().unwrap();
+        let start = JAN_1_2024 + 9 * 3600 + 50 * 60;
+
+        assert_eq!(
+            cron.next_after(time(start)),
+            Some(time(JAN_1_2024 + 17 * 3600))
+        );
+    }
+
+    #[test]
+    fn weekdays() {
+        let cron = "30 9 * * 1-5".parse::<Cron>().unwrap();
+        let saturday = JAN_1_2024 + 5 * 86400;
+
+        assert_eq!(
+            cron.next_after(time(saturday)),
+            Some(time(JAN_1_2024 + 7 * 86400 + 9 * 3600 + 30 * 60))
+        );
+    }
+
+    #[test]
+    fn sunday_as_seven() {
+        let cron = "0 0 * * 7".parse::<Cron>().unwrap();
+
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024)),
+            Some(time(JAN_1_2024 + 6 * 86400))
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_week() {
+        // The 15th of the month or any Sunday
+        let cron = "0 0 15 * 0".parse::<Cron>().unwrap();
+
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024)),
+            Some(time(JAN_1_2024 + 6 * 86400))
+        );
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024 + 8 * 86400)),
+            Some(time(JAN_1_2024 + 13 * 86400))
+        );
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024 + 13 * 86400)),
+            Some(time(JAN_1_2024 + 14 * 86400))
+        );
+    }
+
+    #[test]
+    fn leap_day() {
+        let cron = "0 0 29 2 *".parse::<Cron>().unwrap();
+        let leap_day = JAN_1_2024 + (31 + 28) * 86400;
+
+        assert_eq!(cron.next_after(time(JAN_1_2024)), Some(time(leap_day)));
+        assert!(cron.next_after(time(leap_day)).is_some());
+        assert_eq!(
+            "0 0 30 2 *".parse::<Cron>().unwrap().next_after(time(0)),
+            None
+        );
+
+        // 2100 isn't a leap year, so 2096-02-29 is followed by 2104-02-29
+        assert_eq!(
+            cron.next_after(time(3_981_312_000)),
+            Some(time(4_233_686_400))
+        );
+    }
+
+    #[test]
+    fn unrestricted_fields() {
+        // Fields matching every day aren't restricted, even if not `*`
+        for expr in ["0 0 15 * */1", "0 0 15 * 0-6", "0 0 15 * 1-7"] {
+            let cron = expr.parse::<Cron>().unwrap();
+            assert_eq!(
+                cron.next_after(time(JAN_1_2024)),
+                Some(time(JAN_1_2024 + 14 * 86400)),
+                "{}",
+                expr
+            );
+        }
+
+        let cron = "0 0 1-31 * 0".parse::<Cron>().unwrap();
+        assert_eq!(
+            cron.next_after(time(JAN_1_2024)),
+            Some(time(JAN_1_2024 + 6 * 86400))
+        );
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "5-1 * * * *",
+        ] {
+            assert!(expr.parse::<Cron>().is_err(), "{}", expr);
+        }
+    }
+}
@@ -1,12 +1,15 @@
 //! Terminal handling (TTY interactions, colors, etc)
 
+pub mod color;
 #[cfg(feature = "application")]
 pub mod component;
+pub mod progress;
+pub mod prompt;
 #[macro_use]
 pub mod status;
 pub mod streams;
 
-pub use self::streams::Streams;
+pub use self::{color::ColorMode, streams::Streams};
 pub use termcolor::{Color, ColorChoice, StandardStream};
 
 use once_cell::sync::OnceCell;
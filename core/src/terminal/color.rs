@@ -0,0 +1,148 @@
+//! Terminal color capability detection.
+//!
+//! Whether colors are used when writing to a stream is determined by the
+//! application's [`ColorChoice`] (e.g. from a `--color` option): `Always` and
+//! `Never` are respected as-is, while `Auto` enables colors only when the
+//! following hold, in order of precedence:
+//!
+//! 1. `NO_COLOR` is unset or empty
+//! 2. `CLICOLOR_FORCE` is set to a value other than `0`, in which case colors
+//!    are enabled regardless of the remaining conditions
+//! 3. `CLICOLOR` isn't `0`
+//! 4. the stream is a terminal (TTY)
+//! 5. `TERM` isn't `dumb`
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+use termcolor::ColorChoice;
+
+/// When to use colors, e.g. as specified by a `--color` command-line option.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "options", derive(clap::ValueEnum))]
+pub enum ColorMode {
+    /// Use colors if the output stream supports them
+    #[default]
+    Auto,
+
+    /// Always use colors
+    Always,
+
+    /// Never use colors
+    Never,
+}
+
+impl From<ColorMode> for ColorChoice {
+    fn from(mode: ColorMode) -> ColorChoice {
+        match mode {
+            ColorMode::Auto => ColorChoice::Auto,
+            ColorMode::Always => ColorChoice::Always,
+            ColorMode::Never => ColorChoice::Never,
+        }
+    }
+}
+
+/// Standard streams whose color capabilities can be detected.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Stream {
+    /// Standard output
+    Stdout,
+
+    /// Standard error
+    Stderr,
+}
+
+impl Stream {
+    /// Is this stream a terminal?
+    pub fn is_terminal(self) -> bool {
+        match self {
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+        }
+    }
+}
+
+/// Should colors be used when writing to the given stream?
+pub fn enabled(choice: ColorChoice, stream: Stream) -> bool {
+    match choice {
+        ColorChoice::Always | ColorChoice::AlwaysAnsi => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => detect(|name| env::var(name).ok(), || stream.is_terminal()),
+    }
+}
+
+/// Resolve the given choice for the given stream, replacing `Auto` with
+/// either `Always` or `Never` depending on the stream's capabilities.
+pub fn resolve(choice: ColorChoice, stream: Stream) -> ColorChoice {
+    match choice {
+        ColorChoice::Auto if enabled(choice, stream) => ColorChoice::Always,
+        ColorChoice::Auto => ColorChoice::Never,
+        other => other,
+    }
+}
+
+/// Detect whether colors should be used, given a function for reading
+/// environment variables and one for determining whether the stream is a
+/// terminal.
+fn detect(var: impl Fn(&str) -> Option<String>, is_terminal: impl FnOnce() -> bool) -> bool {
+    if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+
+    if var("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+
+    if var("CLICOLOR").as_deref() == Some("0") {
+        return false;
+    }
+
+    is_terminal() && var("TERM").as_deref() != Some("dumb")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect;
+
+    /// Detect whether colors should be used with the given environment
+    fn detect_with(vars: &[(&str, &str)], is_terminal: bool) -> bool {
+        let var = |name: &str| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| (*value).to_owned())
+        };
+
+        detect(var, || is_terminal)
+    }
+
+    #[test]
+    fn terminal() {
+        assert!(detect_with(&[], true));
+        assert!(detect_with(&[("TERM", "xterm-256color")], true));
+        assert!(!detect_with(&[], false));
+        assert!(!detect_with(&[("TERM", "dumb")], true));
+    }
+
+    #[test]
+    fn no_color() {
+        assert!(!detect_with(&[("NO_COLOR", "1")], true));
+        assert!(!detect_with(
+            &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")],
+            true
+        ));
+        assert!(detect_with(&[("NO_COLOR", "")], true));
+    }
+
+    #[test]
+    fn clicolor() {
+        assert!(!detect_with(&[("CLICOLOR", "0")], true));
+        assert!(detect_with(&[("CLICOLOR", "1")], true));
+        assert!(detect_with(&[("CLICOLOR_FORCE", "1")], false));
+        assert!(detect_with(
+            &[("CLICOLOR_FORCE", "1"), ("TERM", "dumb")],
+            false
+        ));
+        assert!(!detect_with(&[("CLICOLOR_FORCE", "0")], false));
+    }
+}
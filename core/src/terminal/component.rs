@@ -9,15 +9,26 @@ use termcolor::ColorChoice;
 pub struct Terminal {}
 
 impl Terminal {
-    /// Create a new [`Terminal`] component with the given [`ColorChoice`]
+    /// Create a new [`Terminal`] component with the given [`ColorChoice`],
+    /// which is resolved for each stream by the [`color`](super::color) module
     pub fn new(color_choice: ColorChoice) -> Terminal {
         // TODO(tarcieri): handle terminal reinit (without panicking)
         super::init(color_choice);
 
         #[cfg(feature = "backtrace")]
-        if color_choice != ColorChoice::Never {
+        {
+            use super::color::{self, Stream};
+            use color_eyre::config::{HookBuilder, Theme};
+
+            // Error reports are written to stderr, so only use colors if it supports them
+            let hooks = if color::enabled(color_choice, Stream::Stderr) {
+                HookBuilder::default()
+            } else {
+                HookBuilder::default().theme(Theme::new())
+            };
+
             // TODO(tarcieri): avoid panicking here
-            color_eyre::install().expect("couldn't install color-eyre");
+            hooks.install().expect("couldn't install color-eyre");
         }
 
         Self {}
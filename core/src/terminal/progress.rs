@@ -0,0 +1,672 @@
+//! Progress bars and spinners.
+//!
+//! Progress is drawn at the bottom of standard error, below any other output:
+//!
+//! ```ignore
+//! use abscissa_core::terminal::progress::{Bar, Multi, Spinner};
+//!
+//! let bar = Bar::new(total_bytes).with_prefix("Downloading");
+//!
+//! while let Some(chunk) = download.next_chunk()? {
+//!     bar.inc(chunk.len() as u64);
+//! }
+//!
+//! bar.finish_with_message("done");
+//!
+//! // Several lines of progress can be grouped together
+//! let multi = Multi::new();
+//! let compiling = multi.add_spinner().with_prefix("Compiling");
+//! let linking = multi.add_bar(3).with_prefix("Linking");
+//! ```
+//!
+//! Messages printed with the `status_*!` macros (or within [`suspend`]) are
+//! printed above any active progress lines, which are then redrawn.
+//!
+//! Progress is hidden when standard error isn't a terminal (or `TERM=dumb`),
+//! in which case its state is still tracked but nothing is drawn. Lines are
+//! truncated to the terminal width given by the `COLUMNS` environment
+//! variable (or 80 columns if it's unset).
+
+use super::{STREAMS, color::Stream};
+use std::{
+    cell::Cell,
+    env,
+    fmt::Write as _,
+    io::{self, Write},
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Minimum amount of time between redraws caused by progress updates.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Width of the bar itself (in characters).
+const BAR_WIDTH: usize = 30;
+
+/// Terminal width used if `COLUMNS` is unset.
+const DEFAULT_COLUMNS: usize = 80;
+
+/// Width to which prefixes are justified (matching the `status_*!` macros).
+const PREFIX_WIDTH: usize = 12;
+
+/// Frames of the spinner animation.
+const SPINNER_FRAMES: &[char] = &['-', '\\', '|', '/'];
+
+/// Lines of progress being drawn.
+static RENDERER: Mutex<Renderer> = Mutex::new(Renderer::new());
+
+/// Counter used to allocate group IDs.
+static NEXT_GROUP: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Is the current thread running a function passed to [`suspend`]?
+    static SUSPENDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Determinate progress bar, e.g. for downloads.
+///
+/// Dropping a bar which hasn't been finished clears it.
+#[derive(Debug)]
+pub struct Bar(Handle);
+
+impl Bar {
+    /// Create a new progress bar with the given length.
+    pub fn new(length: u64) -> Self {
+        Self(Handle::new(
+            Kind::Bar {
+                position: 0,
+                length,
+            },
+            new_group(),
+        ))
+    }
+
+    /// Set the prefix displayed before the bar, e.g. `Downloading`.
+    pub fn with_prefix(self, prefix: impl Into<String>) -> Self {
+        self.0.update(true, |line| line.prefix = prefix.into());
+        self
+    }
+
+    /// Set the message displayed after the bar.
+    pub fn with_message(self, message: impl Into<String>) -> Self {
+        self.set_message(message);
+        self
+    }
+
+    /// Set the message displayed after the bar.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.0.update(true, |line| line.message = message.into());
+    }
+
+    /// Advance the position of the bar by the given amount.
+    pub fn inc(&self, delta: u64) {
+        self.0.update(false, |line| {
+            if let Kind::Bar { position, .. } = &mut line.kind {
+                *position = position.saturating_add(delta);
+            }
+        });
+    }
+
+    /// Set the position of the bar.
+    pub fn set_position(&self, new_position: u64) {
+        self.0.update(false, |line| {
+            if let Kind::Bar { position, .. } = &mut line.kind {
+                *position = new_position;
+            }
+        });
+    }
+
+    /// Set the length of the bar.
+    pub fn set_length(&self, new_length: u64) {
+        self.0.update(true, |line| {
+            if let Kind::Bar { length, .. } = &mut line.kind {
+                *length = new_length;
+            }
+        });
+    }
+
+    /// Get the position of the bar.
+    pub fn position(&self) -> u64 {
+        match self.0.lock().kind {
+            Kind::Bar { position, .. } => position,
+            Kind::Spinner { .. } => 0,
+        }
+    }
+
+    /// Get the length of the bar.
+    pub fn length(&self) -> u64 {
+        match self.0.lock().kind {
+            Kind::Bar { length, .. } => length,
+            Kind::Spinner { .. } => 0,
+        }
+    }
+
+    /// Is this bar hidden (i.e. not being drawn)?
+    pub fn is_hidden(&self) -> bool {
+        !self.0.visible
+    }
+
+    /// Finish the bar, leaving it displayed in its current state.
+    pub fn finish(&self) {
+        self.0.finish(State::Finished);
+    }
+
+    /// Finish the bar with the given message, leaving it displayed.
+    pub fn finish_with_message(&self, message: impl Into<String>) {
+        self.set_message(message);
+        self.finish();
+    }
+
+    /// Finish the bar and clear it from the terminal.
+    pub fn finish_and_clear(&self) {
+        self.0.finish(State::Cleared);
+    }
+}
+
+/// Spinner, e.g. for tasks whose duration is unknown.
+///
+/// The spinner advances each time it's ticked. Dropping a spinner which
+/// hasn't been finished clears it.
+#[derive(Debug)]
+pub struct Spinner(Handle);
+
+impl Spinner {
+    /// Create a new spinner.
+    pub fn new() -> Self {
+        Self(Handle::new(Kind::Spinner { frame: 0 }, new_group()))
+    }
+
+    /// Set the prefix displayed before the spinner, e.g. `Compiling`.
+    pub fn with_prefix(self, prefix: impl Into<String>) -> Self {
+        self.0.update(true, |line| line.prefix = prefix.into());
+        self
+    }
+
+    /// Set the message displayed after the spinner.
+    pub fn with_message(self, message: impl Into<String>) -> Self {
+        self.set_message(message);
+        self
+    }
+
+    /// Set the message displayed after the spinner.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.0.update(true, |line| line.message = message.into());
+    }
+
+    /// Advance the spinner's animation.
+    pub fn tick(&self) {
+        self.0.update(false, |line| {
+            if let Kind::Spinner { frame } = &mut line.kind {
+                *frame = frame.wrapping_add(1);
+            }
+        });
+    }
+
+    /// Is this spinner hidden (i.e. not being drawn)?
+    pub fn is_hidden(&self) -> bool {
+        !self.0.visible
+    }
+
+    /// Finish the spinner, leaving it displayed in its current state.
+    pub fn finish(&self) {
+        self.0.finish(State::Finished);
+    }
+
+    /// Finish the spinner with the given message, leaving it displayed.
+    pub fn finish_with_message(&self, message: impl Into<String>) {
+        self.set_message(message);
+        self.finish();
+    }
+
+    /// Finish the spinner and clear it from the terminal.
+    pub fn finish_and_clear(&self) {
+        self.0.finish(State::Cleared);
+    }
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Group of progress bars and spinners which are drawn together, in the
+/// order they were added.
+#[derive(Debug)]
+pub struct Multi {
+    /// ID of the group
+    group: u64,
+}
+
+impl Multi {
+    /// Create a new group.
+    pub fn new() -> Self {
+        Self { group: new_group() }
+    }
+
+    /// Add a progress bar with the given length to this group.
+    pub fn add_bar(&self, length: u64) -> Bar {
+        Bar(Handle::new(
+            Kind::Bar {
+                position: 0,
+                length,
+            },
+            self.group,
+        ))
+    }
+
+    /// Add a spinner to this group.
+    pub fn add_spinner(&self) -> Spinner {
+        Spinner(Handle::new(Kind::Spinner { frame: 0 }, self.group))
+    }
+}
+
+impl Default for Multi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run the given function with any active progress cleared from the
+/// terminal, redrawing it afterwards.
+///
+/// Use this when writing to the terminal while progress is being drawn.
+/// Calls may be nested (e.g. printing status messages, which suspend
+/// progress themselves), and progress isn't redrawn by updates made by `f`.
+pub fn suspend<T>(f: impl FnOnce() -> T) -> T {
+    if SUSPENDED.get() {
+        return f();
+    }
+
+    {
+        let mut renderer = lock(&RENDERER);
+
+        if renderer.drawn == 0 {
+            drop(renderer);
+            return f();
+        }
+
+        with_stderr(|out| renderer.clear(out));
+    }
+
+    // The renderer isn't locked while `f` runs, so it can update progress
+    let result = {
+        let _suspended = Suspended::enter();
+        f()
+    };
+
+    let mut renderer = lock(&RENDERER);
+    with_stderr(|out| renderer.redraw(out, columns(), true));
+    result
+}
+
+/// Marks the current thread as suspended until dropped.
+struct Suspended;
+
+impl Suspended {
+    /// Mark the current thread as suspended.
+    fn enter() -> Self {
+        SUSPENDED.set(true);
+        Self
+    }
+}
+
+impl Drop for Suspended {
+    fn drop(&mut self) {
+        SUSPENDED.set(false);
+    }
+}
+
+/// Kind of progress line.
+#[derive(Copy, Clone, Debug)]
+enum Kind {
+    /// Determinate progress bar
+    Bar {
+        /// Current position
+        position: u64,
+
+        /// Total length
+        length: u64,
+    },
+
+    /// Spinner
+    Spinner {
+        /// Current frame of the animation
+        frame: usize,
+    },
+}
+
+/// State of a progress line.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum State {
+    /// Being drawn
+    Active,
+
+    /// Finished, and should be left displayed
+    Finished,
+
+    /// Finished, and should be cleared
+    Cleared,
+}
+
+/// Progress line.
+#[derive(Debug)]
+struct Line {
+    /// Kind of progress line
+    kind: Kind,
+
+    /// Group the line belongs to
+    group: u64,
+
+    /// Prefix displayed before the progress
+    prefix: String,
+
+    /// Message displayed after the progress
+    message: String,
+
+    /// State of the line
+    state: State,
+}
+
+impl Line {
+    /// Render this line, truncating it to the given width.
+    fn render(&self, width: usize) -> String {
+        let mut rendered = format!("{:>width$}", self.prefix, width = PREFIX_WIDTH);
+
+        match self.kind {
+            Kind::Bar { position, length } => {
+                let position = position.min(length);
+                let filled = if length == 0 {
+                    BAR_WIDTH
+                } else {
+                    (position as u128 * BAR_WIDTH as u128 / length as u128) as usize
+                };
+
+                rendered.push_str(" [");
+                rendered.extend(std::iter::repeat_n('=', filled));
+
+                if filled < BAR_WIDTH {
+                    rendered.push('>');
+                    rendered.extend(std::iter::repeat_n(' ', BAR_WIDTH - filled - 1));
+                }
+
+                let _ = write!(rendered, "] {}/{}", position, length);
+            }
+            Kind::Spinner { frame } => {
+                rendered.push(' ');
+                rendered.push(SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]);
+            }
+        }
+
+        if !self.message.is_empty() {
+            rendered.push(' ');
+            rendered.push_str(&self.message);
+        }
+
+        match rendered.char_indices().nth(width) {
+            Some((index, _)) => rendered[..index].to_owned(),
+            None => rendered,
+        }
+    }
+}
+
+/// Handle to a progress line.
+#[derive(Debug)]
+struct Handle {
+    /// Shared state of the line
+    line: Arc<Mutex<Line>>,
+
+    /// Is the line being drawn?
+    visible: bool,
+}
+
+impl Handle {
+    /// Create a new progress line in the given group, which is drawn if
+    /// standard error is a terminal.
+    fn new(kind: Kind, group: u64) -> Self {
+        let line = Arc::new(Mutex::new(Line {
+            kind,
+            group,
+            prefix: String::new(),
+            message: String::new(),
+            state: State::Active,
+        }));
+
+        let visible = Stream::Stderr.is_terminal() && env::var("TERM").as_deref() != Ok("dumb");
+
+        if visible {
+            let mut renderer = lock(&RENDERER);
+            renderer.insert(Arc::clone(&line));
+
+            if !SUSPENDED.get() {
+                with_stderr(|out| renderer.redraw(out, columns(), true));
+            }
+        }
+
+        Self { line, visible }
+    }
+
+    /// Lock the state of the line.
+    fn lock(&self) -> MutexGuard<'_, Line> {
+        lock(&self.line)
+    }
+
+    /// Update the state of the line and redraw it (immediately if `force` is
+    /// set, otherwise subject to rate limiting).
+    fn update(&self, force: bool, f: impl FnOnce(&mut Line)) {
+        f(&mut self.lock());
+
+        if self.visible && !SUSPENDED.get() {
+            let mut renderer = lock(&RENDERER);
+            with_stderr(|out| renderer.redraw(out, columns(), force));
+        }
+    }
+
+    /// Finish the line, if it's still active.
+    fn finish(&self, state: State) {
+        self.update(true, |line| {
+            if line.state == State::Active {
+                line.state = state;
+            }
+        });
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.finish(State::Cleared);
+    }
+}
+
+/// Renderer for the progress lines at the bottom of the terminal.
+#[derive(Debug)]
+struct Renderer {
+    /// Lines being drawn, in order
+    lines: Vec<Arc<Mutex<Line>>>,
+
+    /// Number of lines currently drawn on the terminal
+    drawn: usize,
+
+    /// Time the lines were last drawn
+    last_draw: Option<Instant>,
+}
+
+impl Renderer {
+    /// Create a new renderer.
+    const fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            drawn: 0,
+            last_draw: None,
+        }
+    }
+
+    /// Insert a line, placing it after any other lines in the same group.
+    fn insert(&mut self, line: Arc<Mutex<Line>>) {
+        let group = lock(&line).group;
+
+        match self.lines.iter().rposition(|l| lock(l).group == group) {
+            Some(index) => self.lines.insert(index + 1, line),
+            None => self.lines.push(line),
+        }
+    }
+
+    /// Clear the drawn lines from the terminal.
+    fn clear(&mut self, out: &mut dyn Write) {
+        if self.drawn > 0 {
+            // Move the cursor to the first drawn line and clear the screen below it
+            let _ = write!(out, "\x1b[{}A\r\x1b[J", self.drawn);
+            self.drawn = 0;
+        }
+    }
+
+    /// Draw the lines, first printing (and removing) any which have finished.
+    fn draw(&mut self, out: &mut dyn Write, width: usize) {
+        self.lines.retain(|line| {
+            let line = lock(line);
+
+            match line.state {
+                State::Active => true,
+                State::Finished => {
+                    let _ = writeln!(out, "{}", line.render(width));
+                    false
+                }
+                State::Cleared => false,
+            }
+        });
+
+        for line in &self.lines {
+            let _ = writeln!(out, "{}", lock(line).render(width));
+        }
+
+        let _ = out.flush();
+        self.drawn = self.lines.len();
+        self.last_draw = Some(Instant::now());
+    }
+
+    /// Redraw the lines, unless they were drawn too recently (and `force`
+    /// isn't set).
+    fn redraw(&mut self, out: &mut dyn Write, width: usize, force: bool) {
+        let throttled = self
+            .last_draw
+            .is_some_and(|last_draw| last_draw.elapsed() < REDRAW_INTERVAL);
+
+        if force || !throttled {
+            self.clear(out);
+            self.draw(out, width);
+        }
+    }
+}
+
+/// Allocate a new group ID.
+fn new_group() -> u64 {
+    NEXT_GROUP.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Get the width of the terminal.
+fn columns() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(DEFAULT_COLUMNS)
+}
+
+/// Write to the terminal's standard error stream (or the process's standard
+/// error if the terminal hasn't been initialized).
+fn with_stderr(f: impl FnOnce(&mut dyn Write)) {
+    match STREAMS.get() {
+        Some(streams) => f(&mut streams.stderr.lock()),
+        None => f(&mut io::stderr().lock()),
+    }
+}
+
+/// Lock the given mutex, ignoring poisoning.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Kind, Line, Renderer, State, suspend};
+    use std::sync::{Arc, Mutex};
+
+    /// Create a new progress line
+    fn line(kind: Kind, group: u64, prefix: &str) -> Arc<Mutex<Line>> {
+        Arc::new(Mutex::new(Line {
+            kind,
+            group,
+            prefix: prefix.to_owned(),
+            message: String::new(),
+            state: State::Active,
+        }))
+    }
+
+    #[test]
+    fn render_bar() {
+        let bar = line(
+            Kind::Bar {
+                position: 15,
+                length: 30,
+            },
+            0,
+            "Downloading",
+        );
+        bar.lock().unwrap().message = "file.tar.gz".to_owned();
+
+        assert_eq!(
+            bar.lock().unwrap().render(80),
+            " Downloading [===============>              ] 15/30 file.tar.gz"
+        );
+        assert_eq!(bar.lock().unwrap().render(12), " Downloading");
+    }
+
+    #[test]
+    fn render_spinner() {
+        let spinner = line(Kind::Spinner { frame: 2 }, 0, "Compiling");
+        assert_eq!(spinner.lock().unwrap().render(80), "   Compiling |");
+    }
+
+    #[test]
+    fn nested_suspend() {
+        assert_eq!(suspend(|| suspend(|| 42)), 42);
+    }
+
+    #[test]
+    fn draw_and_finish() {
+        let mut renderer = Renderer::new();
+        let first = line(Kind::Spinner { frame: 0 }, 0, "First");
+        let second = line(Kind::Spinner { frame: 0 }, 1, "Second");
+        let grouped = line(Kind::Spinner { frame: 0 }, 0, "Grouped");
+
+        renderer.insert(Arc::clone(&first));
+        renderer.insert(Arc::clone(&second));
+        renderer.insert(Arc::clone(&grouped));
+
+        let mut out = vec![];
+        renderer.redraw(&mut out, 80, true);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "       First -\n     Grouped -\n      Second -\n"
+        );
+
+        // Finished lines are printed above the active ones
+        first.lock().unwrap().state = State::Finished;
+        grouped.lock().unwrap().state = State::Cleared;
+
+        let mut out = vec![];
+        renderer.redraw(&mut out, 80, true);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[3A\r\x1b[J       First -\n      Second -\n"
+        );
+        assert_eq!(renderer.drawn, 1);
+
+        // Updates within the redraw interval are throttled
+        let mut out = vec![];
+        renderer.redraw(&mut out, 80, false);
+        assert!(out.is_empty());
+    }
+}
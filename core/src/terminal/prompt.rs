@@ -0,0 +1,708 @@
+//! Interactive prompts.
+//!
+//! Prompts are written to standard error (using the application's color
+//! settings) and answers are read from standard input:
+//!
+//! ```ignore
+//! use abscissa_core::terminal::prompt::{Confirm, Input, Password, Select};
+//!
+//! if !Confirm::new("Delete all data?").with_default(false).interact()? {
+//!     return Ok(());
+//! }
+//!
+//! let name = Input::new("Project name")
+//!     .with_validator(|name| {
+//!         if name.contains('/') {
+//!             Err("name can't contain '/'".to_owned())
+//!         } else {
+//!             Ok(())
+//!         }
+//!     })
+//!     .interact()?;
+//!
+//! let license = Select::new("License", ["Apache-2.0", "MIT"]).interact()?;
+//! let password = Password::new("Password").with_confirmation("Confirm password").interact()?;
+//! ```
+//!
+//! Invalid answers are reported and the question is asked again.
+//!
+//! # Scripting
+//!
+//! Prompting fails with a [`TerminalError`] if standard input isn't a
+//! terminal, unless the [`SCRIPTED_ENV_VAR`] environment variable is set
+//! (to a value other than `0`). In that case answers are read line-by-line
+//! from standard input (and passwords aren't masked), which allows tests to
+//! answer prompts by writing to a [`Process`]:
+//!
+//! ```ignore
+//! let mut runner = CmdRunner::default();
+//! runner.env(prompt::SCRIPTED_ENV_VAR, "1").arg("delete");
+//!
+//! let mut process = runner.run();
+//! writeln!(process, "yes")?;
+//! process.wait()?.expect_success();
+//! ```
+//!
+//! [`TerminalError`]: crate::FrameworkErrorKind::TerminalError
+//! [`Process`]: crate::testing::Process
+
+use super::{
+    STREAMS,
+    color::{self, Stream},
+};
+use crate::{
+    FrameworkError,
+    FrameworkErrorKind::{IoError, TerminalError},
+};
+use std::{
+    env, fmt,
+    io::{self, BufRead, IsTerminal},
+};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+#[cfg(feature = "secrets")]
+use secrecy::SecretString;
+
+/// Environment variable which enables reading answers to prompts from a
+/// non-terminal standard input.
+pub const SCRIPTED_ENV_VAR: &str = "ABSCISSA_SCRIPTED_PROMPTS";
+
+/// Validator for text input, returning a message describing why the input
+/// is invalid.
+type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+/// Yes/no question.
+#[derive(Clone, Debug)]
+pub struct Confirm {
+    /// Question to ask
+    prompt: String,
+
+    /// Answer if none is given
+    default: Option<bool>,
+}
+
+impl Confirm {
+    /// Create a new yes/no question.
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            default: None,
+        }
+    }
+
+    /// Set the answer used if none is given.
+    pub fn with_default(mut self, default: bool) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Ask the question.
+    pub fn interact(&self) -> Result<bool, FrameworkError> {
+        interact(|term| self.interact_on(term))
+    }
+
+    /// Ask the question on the given terminal.
+    fn interact_on(&self, term: &mut Term<'_>) -> Result<bool, FrameworkError> {
+        let hint = match self.default {
+            Some(true) => "[Y/n]",
+            Some(false) => "[y/N]",
+            None => "[y/n]",
+        };
+
+        loop {
+            term.prompt(&self.prompt, hint)?;
+
+            let answer = term.read_line()?.to_lowercase();
+
+            match (answer.as_str(), self.default) {
+                ("y" | "yes", _) => return Ok(true),
+                ("n" | "no", _) => return Ok(false),
+                ("", Some(default)) => return Ok(default),
+                _ => term.invalid("please answer 'y' or 'n'")?,
+            }
+        }
+    }
+}
+
+/// Text input, optionally validated.
+pub struct Input {
+    /// Question to ask
+    prompt: String,
+
+    /// Answer if none is given
+    default: Option<String>,
+
+    /// Validator for answers
+    validator: Option<Validator>,
+}
+
+impl Input {
+    /// Create a new text input.
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            default: None,
+            validator: None,
+        }
+    }
+
+    /// Set the answer used if none is given.
+    pub fn with_default(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// Validate answers with the given function, which returns a message
+    /// describing why an answer is invalid.
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Ask for the input.
+    pub fn interact(&self) -> Result<String, FrameworkError> {
+        interact(|term| self.interact_on(term))
+    }
+
+    /// Ask for the input on the given terminal.
+    fn interact_on(&self, term: &mut Term<'_>) -> Result<String, FrameworkError> {
+        let hint = match &self.default {
+            Some(default) => format!("[{}]", default),
+            None => String::new(),
+        };
+
+        loop {
+            term.prompt(&self.prompt, &hint)?;
+
+            let answer = match (term.read_line()?, &self.default) {
+                (answer, Some(default)) if answer.is_empty() => default.clone(),
+                (answer, _) => answer,
+            };
+
+            match self.validator.as_ref().map_or(Ok(()), |v| v(&answer)) {
+                Ok(()) => return Ok(answer),
+                Err(msg) => term.invalid(&msg)?,
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Input")
+            .field("prompt", &self.prompt)
+            .field("default", &self.default)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Choice of one item from a list.
+#[derive(Clone, Debug)]
+pub struct Select {
+    /// Question to ask
+    prompt: String,
+
+    /// Items to choose from
+    items: Vec<String>,
+
+    /// Index of the item chosen if none is given
+    default: Option<usize>,
+}
+
+impl Select {
+    /// Create a new choice between the given items.
+    pub fn new<I, S>(prompt: impl Into<String>, items: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            prompt: prompt.into(),
+            items: items.into_iter().map(Into::into).collect(),
+            default: None,
+        }
+    }
+
+    /// Set the index of the item chosen if none is given.
+    ///
+    /// Asking for a choice fails if the index is out of range.
+    pub fn with_default(mut self, default: usize) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Ask for a choice, returning the index of the chosen item.
+    pub fn interact(&self) -> Result<usize, FrameworkError> {
+        interact(|term| self.interact_on(term))
+    }
+
+    /// Ask for a choice on the given terminal.
+    fn interact_on(&self, term: &mut Term<'_>) -> Result<usize, FrameworkError> {
+        ensure!(
+            !self.items.is_empty(),
+            TerminalError,
+            "no items to select from"
+        );
+
+        if let Some(default) = self.default {
+            ensure!(
+                default < self.items.len(),
+                TerminalError,
+                "default index {} is out of range for {} items",
+                default,
+                self.items.len()
+            );
+        }
+
+        let hint = match self.default {
+            Some(default) => format!("[1-{}, default {}]", self.items.len(), default + 1),
+            None => format!("[1-{}]", self.items.len()),
+        };
+
+        loop {
+            term.prompt(&self.prompt, "")?;
+            term.items(&self.items, |index| self.default == Some(index))?;
+            term.prompt("Enter a number", &hint)?;
+
+            let answer = term.read_line()?;
+
+            let choice = match self.default {
+                Some(default) if answer.is_empty() => Ok(default),
+                _ => parse_choice(&answer, self.items.len()),
+            };
+
+            match choice {
+                Ok(index) => return Ok(index),
+                Err(msg) => term.invalid(&msg)?,
+            }
+        }
+    }
+}
+
+/// Choice of any number of items from a list.
+#[derive(Clone, Debug)]
+pub struct MultiSelect {
+    /// Question to ask
+    prompt: String,
+
+    /// Items to choose from
+    items: Vec<String>,
+
+    /// Indexes of the items chosen if none are given
+    defaults: Vec<usize>,
+}
+
+impl MultiSelect {
+    /// Create a new choice between the given items.
+    pub fn new<I, S>(prompt: impl Into<String>, items: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            prompt: prompt.into(),
+            items: items.into_iter().map(Into::into).collect(),
+            defaults: vec![],
+        }
+    }
+
+    /// Set the indexes of the items chosen if none are given.
+    ///
+    /// Asking for a choice fails if any index is out of range.
+    pub fn with_defaults(mut self, defaults: impl IntoIterator<Item = usize>) -> Self {
+        self.defaults = defaults.into_iter().collect();
+        self
+    }
+
+    /// Ask for a choice, returning the indexes of the chosen items (in
+    /// ascending order).
+    pub fn interact(&self) -> Result<Vec<usize>, FrameworkError> {
+        interact(|term| self.interact_on(term))
+    }
+
+    /// Ask for a choice on the given terminal.
+    fn interact_on(&self, term: &mut Term<'_>) -> Result<Vec<usize>, FrameworkError> {
+        ensure!(
+            !self.items.is_empty(),
+            TerminalError,
+            "no items to select from"
+        );
+
+        if let Some(default) = self
+            .defaults
+            .iter()
+            .find(|&&index| index >= self.items.len())
+        {
+            fail!(
+                TerminalError,
+                "default index {} is out of range for {} items",
+                default,
+                self.items.len()
+            );
+        }
+
+        let defaults = self
+            .defaults
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect::<Vec<_>>();
+
+        let hint = format!("[default: {}]", defaults.join(","));
+
+        loop {
+            term.prompt(&self.prompt, "")?;
+            term.items(&self.items, |index| self.defaults.contains(&index))?;
+            term.prompt("Enter numbers separated by commas", &hint)?;
+
+            let answer = term.read_line()?;
+
+            if answer.is_empty() {
+                let mut chosen = self.defaults.clone();
+                chosen.sort_unstable();
+                chosen.dedup();
+                return Ok(chosen);
+            }
+
+            let choices = answer
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|choice| !choice.is_empty())
+                .map(|choice| parse_choice(choice, self.items.len()))
+                .collect::<Result<Vec<_>, _>>();
+
+            match choices {
+                Ok(mut chosen) => {
+                    chosen.sort_unstable();
+                    chosen.dedup();
+                    return Ok(chosen);
+                }
+                Err(msg) => term.invalid(&msg)?,
+            }
+        }
+    }
+}
+
+/// Password (or other secret) input, which isn't echoed to the terminal.
+#[cfg(feature = "secrets")]
+#[derive(Clone, Debug)]
+pub struct Password {
+    /// Question to ask
+    prompt: String,
+
+    /// Question to ask when confirming the password (if it's confirmed)
+    confirmation: Option<String>,
+
+    /// Is an empty password allowed?
+    allow_empty: bool,
+}
+
+#[cfg(feature = "secrets")]
+impl Password {
+    /// Create a new password input.
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            confirmation: None,
+            allow_empty: false,
+        }
+    }
+
+    /// Require the password to be entered twice, asking the given question
+    /// the second time.
+    pub fn with_confirmation(mut self, prompt: impl Into<String>) -> Self {
+        self.confirmation = Some(prompt.into());
+        self
+    }
+
+    /// Allow an empty password.
+    pub fn allow_empty(mut self) -> Self {
+        self.allow_empty = true;
+        self
+    }
+
+    /// Ask for the password.
+    pub fn interact(&self) -> Result<SecretString, FrameworkError> {
+        interact(|term| self.interact_on(term))
+    }
+
+    /// Ask for the password on the given terminal.
+    fn interact_on(&self, term: &mut Term<'_>) -> Result<SecretString, FrameworkError> {
+        loop {
+            term.prompt(&self.prompt, "")?;
+            let password = term.read_password()?;
+
+            if password.is_empty() && !self.allow_empty {
+                term.invalid("password can't be empty")?;
+                continue;
+            }
+
+            if let Some(confirmation) = &self.confirmation {
+                term.prompt(confirmation, "")?;
+
+                if term.read_password()? != password {
+                    term.invalid("passwords don't match")?;
+                    continue;
+                }
+            }
+
+            return Ok(password.into());
+        }
+    }
+}
+
+/// Terminal on which prompts are displayed and answered.
+struct Term<'a> {
+    /// Input the answers are read from
+    input: &'a mut dyn BufRead,
+
+    /// Output the prompts are written to
+    output: &'a mut dyn WriteColor,
+
+    /// Are answers (including passwords) read from `input` as plain lines?
+    scripted: bool,
+}
+
+impl Term<'_> {
+    /// Display a prompt, followed by the given hint (if non-empty).
+    fn prompt(&mut self, prompt: &str, hint: &str) -> Result<(), FrameworkError> {
+        self.output
+            .set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+        write!(self.output, "? ")?;
+        self.output.set_color(ColorSpec::new().set_bold(true))?;
+        write!(self.output, "{}", prompt)?;
+        self.output.reset()?;
+
+        if hint.is_empty() {
+            writeln!(self.output)?;
+        } else {
+            write!(self.output, " {}: ", hint)?;
+        }
+
+        self.output.flush()?;
+        Ok(())
+    }
+
+    /// Display a numbered list of items, marking the selected ones.
+    fn items(
+        &mut self,
+        items: &[String],
+        selected: impl Fn(usize) -> bool,
+    ) -> Result<(), FrameworkError> {
+        for (index, item) in items.iter().enumerate() {
+            let marker = if selected(index) { '*' } else { ' ' };
+            writeln!(self.output, "  {} {}) {}", marker, index + 1, item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Report an invalid answer.
+    fn invalid(&mut self, msg: &str) -> Result<(), FrameworkError> {
+        self.output
+            .set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))?;
+        write!(self.output, "error:")?;
+        self.output.reset()?;
+        writeln!(self.output, " {}", msg)?;
+        Ok(())
+    }
+
+    /// Read an answer, without surrounding whitespace.
+    fn read_line(&mut self) -> Result<String, FrameworkError> {
+        Ok(self.read_input()?.trim().to_owned())
+    }
+
+    /// Read a password without echoing it (unless scripted).
+    #[cfg(feature = "secrets")]
+    fn read_password(&mut self) -> Result<String, FrameworkError> {
+        if self.scripted {
+            self.read_input()
+        } else {
+            Ok(rpassword::read_password()?)
+        }
+    }
+
+    /// Read a line of input, without its trailing newline.
+    fn read_input(&mut self) -> Result<String, FrameworkError> {
+        let mut line = String::new();
+
+        if self.input.read_line(&mut line)? == 0 {
+            fail!(IoError, "unexpected end of input while prompting");
+        }
+
+        // Scripted input isn't echoed, so end the prompt's line
+        if self.scripted {
+            writeln!(self.output)?;
+        }
+
+        Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+    }
+}
+
+/// Run the given function on the terminal.
+///
+/// Fails unless standard input is a terminal or scripted prompts are enabled.
+fn interact<T>(
+    f: impl FnOnce(&mut Term<'_>) -> Result<T, FrameworkError>,
+) -> Result<T, FrameworkError> {
+    let scripted = if io::stdin().is_terminal() {
+        false
+    } else if scripted_enabled() {
+        true
+    } else {
+        fail!(
+            TerminalError,
+            "can't prompt for input: stdin is not a terminal (set {} to read answers from stdin)",
+            SCRIPTED_ENV_VAR
+        );
+    };
+
+    let mut input = io::stdin().lock();
+
+    match STREAMS.get() {
+        Some(streams) => f(&mut Term {
+            input: &mut input,
+            output: &mut streams.stderr.lock(),
+            scripted,
+        }),
+        None => {
+            let stderr = StandardStream::stderr(color::resolve(ColorChoice::Auto, Stream::Stderr));
+
+            f(&mut Term {
+                input: &mut input,
+                output: &mut stderr.lock(),
+                scripted,
+            })
+        }
+    }
+}
+
+/// Are scripted prompts enabled by the environment?
+fn scripted_enabled() -> bool {
+    env::var_os(SCRIPTED_ENV_VAR).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Parse the (1-based) number of an item from a list of the given length,
+/// returning its index.
+fn parse_choice(choice: &str, len: usize) -> Result<usize, String> {
+    match choice.trim().parse::<usize>() {
+        Ok(n) if (1..=len).contains(&n) => Ok(n - 1),
+        _ => Err(format!("please enter a number from 1 to {}", len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Confirm, Input, MultiSelect, Select, Term};
+    use std::io::Cursor;
+    use termcolor::NoColor;
+
+    /// Answer a prompt with the given input, returning the result and output
+    fn answer<T>(input: &str, f: impl FnOnce(&mut Term<'_>) -> T) -> (T, String) {
+        let mut input = Cursor::new(input.as_bytes());
+        let mut output = NoColor::new(vec![]);
+
+        let result = f(&mut Term {
+            input: &mut input,
+            output: &mut output,
+            scripted: true,
+        });
+
+        (result, String::from_utf8(output.into_inner()).unwrap())
+    }
+
+    #[test]
+    fn confirm() {
+        let confirm = Confirm::new("Continue?").with_default(false);
+
+        let (result, output) = answer("maybe\nYes\n", |term| confirm.interact_on(term));
+        assert!(result.unwrap());
+        assert_eq!(
+            output,
+            "? Continue? [y/N]: \nerror: please answer 'y' or 'n'\n? Continue? [y/N]: \n"
+        );
+
+        let (result, _) = answer("\n", |term| confirm.interact_on(term));
+        assert!(!result.unwrap());
+
+        // Running out of input is an error rather than an endless loop
+        let (result, _) = answer("", |term| Confirm::new("Continue?").interact_on(term));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn input() {
+        let input = Input::new("Name")
+            .with_default("default")
+            .with_validator(|name| {
+                if name.contains('/') {
+                    Err("invalid name".to_owned())
+                } else {
+                    Ok(())
+                }
+            });
+
+        let (result, output) = answer("a/b\n  my-app \n", |term| input.interact_on(term));
+        assert_eq!(result.unwrap(), "my-app");
+        assert!(output.contains("error: invalid name"));
+
+        let (result, _) = answer("\n", |term| input.interact_on(term));
+        assert_eq!(result.unwrap(), "default");
+    }
+
+    #[test]
+    fn select() {
+        let select = Select::new("License", ["Apache-2.0", "MIT"]).with_default(1);
+
+        let (result, output) = answer("3\n1\n", |term| select.interact_on(term));
+        assert_eq!(result.unwrap(), 0);
+        assert!(output.starts_with(
+            "? License\n    1) Apache-2.0\n  * 2) MIT\n? Enter a number [1-2, default 2]: "
+        ));
+        assert!(output.contains("error: please enter a number from 1 to 2"));
+
+        let (result, _) = answer("\n", |term| select.interact_on(term));
+        assert_eq!(result.unwrap(), 1);
+
+        let select = Select::new("License", ["Apache-2.0", "MIT"]).with_default(2);
+        let (result, output) = answer("\n", |term| select.interact_on(term));
+        assert!(result.is_err());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn multi_select() {
+        let select = MultiSelect::new("Features", ["a", "b", "c"]).with_defaults([2]);
+
+        let (result, _) = answer("3, 1 3\n", |term| select.interact_on(term));
+        assert_eq!(result.unwrap(), [0, 2]);
+
+        let (result, _) = answer("\n", |term| select.interact_on(term));
+        assert_eq!(result.unwrap(), [2]);
+
+        let select = MultiSelect::new("Features", ["a", "b", "c"]).with_defaults([0, 3]);
+        let (result, _) = answer("\n", |term| select.interact_on(term));
+        assert!(result.is_err());
+
+        let select = MultiSelect::new("Features", Vec::<String>::new());
+        let (result, _) = answer("\n", |term| select.interact_on(term));
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "secrets")]
+    #[test]
+    fn password() {
+        use super::Password;
+        use secrecy::ExposeSecret;
+
+        let password = Password::new("Password").with_confirmation("Confirm password");
+
+        let (result, output) = answer("\nhunter2 \nhunter3\nhunter2 \nhunter2 \n", |term| {
+            password.interact_on(term)
+        });
+        assert_eq!(result.unwrap().expose_secret(), "hunter2 ");
+        assert!(output.contains("error: password can't be empty"));
+        assert!(output.contains("error: passwords don't match"));
+    }
+}
@@ -30,12 +30,52 @@
 //! // Print an error attribute to STDERR
 //! status_attr_err!("error", "yep");
 //! ```
+//!
+//! # Verbosity
+//!
+//! Which messages are printed depends on the current verbosity (see
+//! [`set_verbosity`]): quiet mode suppresses informational messages, while
+//! errors are always printed.
 
-use super::{stderr, stdout};
+use super::{progress, stderr, stdout};
 use crate::FrameworkError;
-use std::io::Write;
+use std::{
+    io::Write,
+    sync::atomic::{AtomicI8, Ordering},
+};
 use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
 
+/// Current verbosity of status messages.
+static VERBOSITY: AtomicI8 = AtomicI8::new(0);
+
+/// Set the verbosity of status messages, where negative values are quieter:
+///
+/// - `0` or above: all messages are printed
+/// - `-1`: `status_ok!`, `status_info!`, and `status_attr_ok!` are suppressed
+/// - `-2` or below: `status_warn!` is also suppressed
+///
+/// Errors are always printed.
+pub fn set_verbosity(verbosity: i8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+/// Get the current verbosity of status messages.
+pub fn verbosity() -> i8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Are informational status messages printed at the current verbosity?
+#[doc(hidden)]
+pub fn info_enabled() -> bool {
+    verbosity() >= 0
+}
+
+/// Are warnings printed at the current verbosity?
+#[doc(hidden)]
+pub fn warn_enabled() -> bool {
+    verbosity() >= -1
+}
+
 /// Print a success status message (in green if colors are enabled)
 ///
 /// ```ignore
@@ -45,13 +85,15 @@ use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
 #[macro_export]
 macro_rules! status_ok {
     ($status:expr, $msg:expr) => {
-        $crate::terminal::status::Status::new()
-            .justified()
-            .bold()
-            .color($crate::terminal::Color::Green)
-            .status($status)
-            .print_stderr($msg)
-            .unwrap();
+        if $crate::terminal::status::info_enabled() {
+            $crate::terminal::status::Status::new()
+                .justified()
+                .bold()
+                .color($crate::terminal::Color::Green)
+                .status($status)
+                .print_stderr($msg)
+                .unwrap();
+        }
     };
     ($status:expr, $fmt:expr, $($arg:tt)+) => {
         $crate::status_ok!($status, format!($fmt, $($arg)+));
@@ -67,13 +109,15 @@ macro_rules! status_ok {
 #[macro_export]
 macro_rules! status_info {
     ($status:expr, $msg:expr) => {
-        $crate::terminal::status::Status::new()
-            .justified()
-            .bold()
-            .color($crate::terminal::Color::Cyan)
-            .status($status)
-            .print_stderr($msg)
-            .unwrap();
+        if $crate::terminal::status::info_enabled() {
+            $crate::terminal::status::Status::new()
+                .justified()
+                .bold()
+                .color($crate::terminal::Color::Cyan)
+                .status($status)
+                .print_stderr($msg)
+                .unwrap();
+        }
     };
     ($status:expr, $fmt:expr, $($arg:tt)+) => {
         $crate::status_info!($status, format!($fmt, $($arg)+));
@@ -89,12 +133,14 @@ macro_rules! status_info {
 #[macro_export]
 macro_rules! status_warn {
     ($msg:expr) => {
-        $crate::terminal::status::Status::new()
-            .bold()
-            .color($crate::terminal::Color::Yellow)
-            .status("warning:")
-            .print_stderr($msg)
-            .unwrap();
+        if $crate::terminal::status::warn_enabled() {
+            $crate::terminal::status::Status::new()
+                .bold()
+                .color($crate::terminal::Color::Yellow)
+                .status("warning:")
+                .print_stderr($msg)
+                .unwrap();
+        }
     };
     ($fmt:expr, $($arg:tt)+) => {
         $crate::status_warn!(format!($fmt, $($arg)+));
@@ -131,20 +177,22 @@ macro_rules! status_err {
 #[macro_export]
 macro_rules! status_attr_ok {
     ($attr:expr, $msg:expr) => {
-        // TODO(tarcieri): hax... use a better format string?
-        let attr_delimited = if $attr.len() >= 7 {
-            format!("{}:", $attr)
-        } else {
-            format!("{}:\t", $attr)
-        };
+        if $crate::terminal::status::info_enabled() {
+            // TODO(tarcieri): hax... use a better format string?
+            let attr_delimited = if $attr.len() >= 7 {
+                format!("{}:", $attr)
+            } else {
+                format!("{}:\t", $attr)
+            };
 
 
-        $crate::terminal::status::Status::new()
-            .bold()
-            .color($crate::terminal::Color::Green)
-            .status(attr_delimited)
-            .print_stdout($msg)
-            .unwrap();
+            $crate::terminal::status::Status::new()
+                .bold()
+                .color($crate::terminal::Color::Green)
+                .status(attr_delimited)
+                .print_stdout($msg)
+                .unwrap();
+        }
     };
     ($attr: expr, $fmt:expr, $($arg:tt)+) => {
         $crate::status_attr_ok!($attr, format!($fmt, $($arg)+));
@@ -245,11 +293,16 @@ impl Status {
         self.print(stderr(), msg)
     }
 
-    /// Print the given message
+    /// Print the given message (above any progress being drawn)
     fn print<S>(self, stream: &StandardStream, msg: S) -> Result<(), FrameworkError>
     where
         S: AsRef<str>,
     {
+        progress::suspend(|| self.print_line(stream, msg.as_ref()))
+    }
+
+    /// Print the given message as a line
+    fn print_line(self, stream: &StandardStream, msg: &str) -> Result<(), FrameworkError> {
         let mut s = stream.lock();
         s.reset()?;
         s.set_color(ColorSpec::new().set_fg(self.color).set_bold(self.bold))?;
@@ -263,7 +316,6 @@ impl Status {
         }
 
         s.reset()?;
-        let msg = msg.as_ref();
         if !msg.is_empty() {
             writeln!(s, " {}", msg)?;
         }
@@ -1,5 +1,6 @@
 //! Terminal streams (STDOUT and STDIN)
 
+use super::color::{self, Stream};
 use termcolor::{ColorChoice, StandardStream};
 
 /// Terminal streams
@@ -12,11 +13,12 @@ pub struct Streams {
 }
 
 impl Streams {
-    /// Create a new set of terminal streams
+    /// Create a new set of terminal streams, using colors on each of them
+    /// only if it supports them (see the [`color`] module)
     pub fn new(color_choice: ColorChoice) -> Self {
         Self {
-            stdout: StandardStream::stdout(color_choice),
-            stderr: StandardStream::stderr(color_choice),
+            stdout: StandardStream::stdout(color::resolve(color_choice, Stream::Stdout)),
+            stderr: StandardStream::stderr(color::resolve(color_choice, Stream::Stderr)),
         }
     }
 }
@@ -13,5 +13,7 @@ pub mod prelude;
 pub mod process;
 mod regex;
 mod runner;
+#[cfg(feature = "trace")]
+pub mod trace;
 
 pub use self::{config::ConfigFile, regex::Regex, runner::CmdRunner};
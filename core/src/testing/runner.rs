@@ -32,6 +32,9 @@ pub struct CmdRunner {
     /// Arguments to pass to the executable
     args: Vec<OsString>,
 
+    /// Environment variables to set for the executable
+    envs: Vec<(OsString, OsString)>,
+
     /// Capture standard output to a pipe
     capture_stdout: bool,
 
@@ -100,6 +103,7 @@ impl CmdRunner {
             program: program.into(),
             target_bin: None,
             args: vec![],
+            envs: vec![],
             capture_stdout: false,
             capture_stderr: false,
             config: None,
@@ -128,6 +132,16 @@ impl CmdRunner {
         self
     }
 
+    /// Set an environment variable for the command
+    pub fn env<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        K: Into<OsString>,
+        V: Into<OsString>,
+    {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
     /// Enable capturing of standard output
     pub fn capture_stdout(&mut self) -> &mut Self {
         self.capture_stdout = true;
@@ -212,6 +226,7 @@ impl CmdRunner {
 
         let child = Command::new(&self.program)
             .args(&self.args)
+            .envs(self.envs.iter().map(|(key, value)| (key, value)))
             .stdin(Stdio::piped())
             .stdout(stdout)
             .stderr(stderr)
@@ -0,0 +1,246 @@
+//! Capturing tracing events in tests.
+//!
+//! [`capture`] installs a subscriber which records the events logged by the
+//! current thread until the returned [`Capture`] is dropped. As it's only
+//! the default subscriber for the current thread, it can be used in any
+//! number of tests, regardless of whether a global subscriber is installed.
+//!
+//! ```
+//! use abscissa_core::{assert_logged, assert_not_logged, testing::trace};
+//! use tracing::Level;
+//!
+//! let capture = trace::capture();
+//! tracing::warn!(user = "alice", "access denied");
+//!
+//! assert_logged!(Level::WARN, "access denied");
+//! assert_not_logged!(Level::ERROR, ".*");
+//! assert_eq!(capture.events()[0].fields["user"], "alice");
+//! ```
+
+use super::Regex;
+use crate::{Map, trace::fields::Fields};
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+use tracing::{Level, Subscriber, subscriber::DefaultGuard};
+use tracing_subscriber::{
+    Layer, Registry,
+    layer::{Context, SubscriberExt},
+};
+
+/// Events recorded by a capture.
+type Events = Arc<Mutex<Vec<Event>>>;
+
+thread_local! {
+    /// Events being recorded by the current thread's capture (if any).
+    static CURRENT: RefCell<Option<Events>> = const { RefCell::new(None) };
+}
+
+/// Assert that an event with the given level and a message matching the
+/// given regex was captured by the current thread's [`Capture`].
+#[macro_export]
+macro_rules! assert_logged {
+    ($level:expr, $re:expr) => {
+        $crate::testing::trace::assert_logged($level, $re)
+    };
+    ($level:expr, $fmt:expr, $($arg:tt)+) => {
+        $crate::assert_logged!($level, format!($fmt, $($arg)+).as_str())
+    };
+}
+
+/// Assert that no events with the given level and a message matching the
+/// given regex were captured by the current thread's [`Capture`].
+#[macro_export]
+macro_rules! assert_not_logged {
+    ($level:expr, $re:expr) => {
+        $crate::testing::trace::assert_not_logged($level, $re)
+    };
+    ($level:expr, $fmt:expr, $($arg:tt)+) => {
+        $crate::assert_not_logged!($level, format!($fmt, $($arg)+).as_str())
+    };
+}
+
+/// Captured tracing event.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Event {
+    /// Level of the event
+    pub level: Level,
+
+    /// Target of the event
+    pub target: String,
+
+    /// Message of the event
+    pub message: String,
+
+    /// Other fields of the event
+    pub fields: Map<String, String>,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.level, self.target, self.message)?;
+
+        for (name, value) in &self.fields {
+            write!(f, " {}={}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Capture of the events logged by the current thread.
+///
+/// Events are recorded until this is dropped.
+#[derive(Debug)]
+pub struct Capture {
+    /// Events which have been recorded
+    events: Events,
+
+    /// Previous capture of the current thread (if any), restored on drop
+    previous: Option<Events>,
+
+    /// Guard which restores the previous default subscriber on drop
+    _guard: DefaultGuard,
+}
+
+impl Capture {
+    /// Get the events which have been captured.
+    pub fn events(&self) -> Vec<Event> {
+        lock(&self.events).clone()
+    }
+
+    /// Get the captured events with the given level and a message matching
+    /// the given regex.
+    pub fn matching(&self, level: Level, re: impl Into<Regex>) -> Vec<Event> {
+        let re = re.into();
+
+        lock(&self.events)
+            .iter()
+            .filter(|event| event.level == level && re.is_match(&event.message))
+            .cloned()
+            .collect()
+    }
+
+    /// Clear the events which have been captured.
+    pub fn clear(&self) {
+        lock(&self.events).clear();
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Capture the events logged by the current thread until the returned
+/// [`Capture`] is dropped.
+pub fn capture() -> Capture {
+    let events = Events::default();
+    let layer = CaptureLayer {
+        events: Arc::clone(&events),
+    };
+
+    let guard = tracing::subscriber::set_default(Registry::default().with(layer));
+    let previous = CURRENT.with(|current| current.borrow_mut().replace(Arc::clone(&events)));
+
+    Capture {
+        events,
+        previous,
+        _guard: guard,
+    }
+}
+
+/// Assert that an event with the given level and a message matching the
+/// given regex was captured by the current thread's [`Capture`].
+///
+/// Panics if the current thread isn't capturing events.
+pub fn assert_logged(level: Level, re: impl Into<Regex>) {
+    let re = re.into();
+    let events = current_events();
+
+    if !events
+        .iter()
+        .any(|event| event.level == level && re.is_match(&event.message))
+    {
+        panic!(
+            "no {} event matching {:?} was logged; captured events:\n{}",
+            level,
+            re,
+            format_events(&events)
+        );
+    }
+}
+
+/// Assert that no events with the given level and a message matching the
+/// given regex were captured by the current thread's [`Capture`].
+///
+/// Panics if the current thread isn't capturing events.
+pub fn assert_not_logged(level: Level, re: impl Into<Regex>) {
+    let re = re.into();
+    let matching = current_events()
+        .into_iter()
+        .filter(|event| event.level == level && re.is_match(&event.message))
+        .collect::<Vec<_>>();
+
+    if !matching.is_empty() {
+        panic!(
+            "unexpected {} event(s) matching {:?} were logged:\n{}",
+            level,
+            re,
+            format_events(&matching)
+        );
+    }
+}
+
+/// Layer which records events.
+struct CaptureLayer {
+    /// Events which have been recorded
+    events: Events,
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let (message, fields) = Fields::from_event(event);
+
+        lock(&self.events).push(Event {
+            level: *metadata.level(),
+            target: metadata.target().to_owned(),
+            message,
+            fields: fields
+                .0
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect(),
+        });
+    }
+}
+
+/// Get the events captured by the current thread.
+fn current_events() -> Vec<Event> {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(events) => lock(events).clone(),
+        None => panic!("tracing events aren't being captured (use `testing::trace::capture`)"),
+    })
+}
+
+/// Lock the given events, ignoring poisoning (e.g. by a failed assertion).
+fn lock(events: &Events) -> MutexGuard<'_, Vec<Event>> {
+    events.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Format events for display in an assertion failure.
+fn format_events(events: &[Event]) -> String {
+    if events.is_empty() {
+        return "  (none)".to_owned();
+    }
+
+    events
+        .iter()
+        .map(|event| format!("  {}", event))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -2,18 +2,44 @@
 //!
 //! These types provide simple wrappers for Rust's core threading primitives.
 
+mod handle;
+mod info;
 mod kill_switch;
 pub mod manager;
 mod name;
+#[cfg(feature = "application")]
+pub mod pool;
+pub mod supervisor;
 
-pub use self::{manager::Manager, name::Name};
+pub use self::{
+    handle::Handle,
+    info::{Info, State},
+    manager::Manager,
+    name::Name,
+    supervisor::{Failure, Policy, Status},
+};
 
-use self::kill_switch::KillSwitch;
-use crate::{FrameworkError, FrameworkErrorKind::ThreadError};
-use std::{io, sync::Arc, thread};
+#[cfg(feature = "application")]
+pub use self::pool::Pool;
+
+use self::{
+    handle::ResultSlot,
+    info::{ExitState, PanicGuard},
+    kill_switch::KillSwitch,
+    supervisor::{EscalationHandler, SharedStatus},
+};
+use crate::{FrameworkError, FrameworkErrorKind::ThreadError, error::BoxError};
+#[cfg(feature = "application")]
+use crossbeam_channel::Receiver;
+use std::{
+    io,
+    sync::{Arc, PoisonError},
+    thread,
+    time::{Duration, SystemTime},
+};
 
 /// Join handles for Abscissa-managed threads.
-pub type JoinHandle = thread::JoinHandle<()>;
+pub type JoinHandle = thread::JoinHandle<Result<(), FrameworkError>>;
 
 /// Threads spawned and managed by Abscissa
 #[derive(Debug)]
@@ -26,6 +52,15 @@ pub struct Thread {
 
     /// Join handle to the thread
     handle: JoinHandle,
+
+    /// Supervision status of the thread
+    status: SharedStatus,
+
+    /// Time at which the thread was spawned
+    started: SystemTime,
+
+    /// How the thread exited (if it has)
+    exit: ExitState,
 }
 
 impl Thread {
@@ -34,13 +69,76 @@ impl Thread {
     where
         F: FnOnce() + Send + 'static,
     {
-        let kill_switch = Arc::new(KillSwitch::new());
-        let handle = spawn_thread(name.clone(), Arc::clone(&kill_switch), f)?;
+        let status = SharedStatus::default();
+        let thread_status = Arc::clone(&status);
+        let thread_name = name.clone();
 
-        Ok(Self {
-            name,
-            kill_switch,
-            handle,
+        Self::spawn_with_status(name, status, move || {
+            supervisor::run_once(&thread_name, &thread_status, None, f);
+            Ok(())
+        })
+    }
+
+    /// Spawn a new thread, executing the given runnable which returns a
+    /// result, along with a typed handle to the value it returns.
+    ///
+    /// If an escalation handler is given, it's invoked if the thread panics
+    /// or returns an error.
+    pub fn spawn_result<T, E, F>(
+        name: Name,
+        escalation: Option<EscalationHandler>,
+        f: F,
+    ) -> Result<(Self, Handle<T>), FrameworkError>
+    where
+        T: Send + 'static,
+        E: Into<BoxError>,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+    {
+        let status = SharedStatus::default();
+        let thread_status = Arc::clone(&status);
+        let thread_name = name.clone();
+        let result = ResultSlot::default();
+        let thread_result = Arc::clone(&result);
+
+        let thread = Self::spawn_with_status(name.clone(), status, move || {
+            let escalation = escalation.as_ref();
+
+            match supervisor::run_once(&thread_name, &thread_status, escalation, f) {
+                Ok(value) => {
+                    *thread_result.lock().unwrap_or_else(PoisonError::into_inner) = Some(value);
+                    Ok(())
+                }
+                Err(e) => {
+                    let message = e.into().to_string();
+                    let failure =
+                        supervisor::report_error(&thread_name, &thread_status, escalation, message);
+
+                    fail!(ThreadError, "thread '{}' failed: {}", thread_name, failure)
+                }
+            }
+        })?;
+
+        Ok((thread, Handle::new(name, result)))
+    }
+
+    /// Spawn a new thread, executing the given runnable under the given
+    /// supervision policy.
+    pub fn spawn_supervised<F>(
+        name: Name,
+        policy: Policy,
+        escalation: EscalationHandler,
+        f: F,
+    ) -> Result<Self, FrameworkError>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let status = SharedStatus::default();
+        let thread_status = Arc::clone(&status);
+        let thread_name = name.clone();
+
+        Self::spawn_with_status(name, status, move || {
+            supervisor::supervise(&thread_name, policy, &thread_status, &escalation, f);
+            Ok(())
         })
     }
 
@@ -49,11 +147,43 @@ impl Thread {
         &self.name
     }
 
+    /// Get the supervision status of this thread.
+    pub fn status(&self) -> Status {
+        supervisor::lock(&self.status).clone()
+    }
+
+    /// Get information about this thread, including its current state.
+    pub fn info(&self) -> Info {
+        let state = match self.exit.get() {
+            Some(state) => state,
+            None if self.kill_switch.is_thrown() => State::Terminating,
+            None => State::Running,
+        };
+
+        Info {
+            name: self.name.clone(),
+            started: self.started,
+            state,
+            restarts: self.status().restarts,
+        }
+    }
+
+    /// Has this thread finished running?
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Is this the currently running thread?
+    pub(super) fn is_current(&self) -> bool {
+        self.handle.thread().id() == thread::current().id()
+    }
+
     /// Request that this thread terminate.
     ///
     /// Note this does not have immediate effect: it signals to the thread
-    /// that it should exit, however the target thread needs to poll the
-    /// `Thread::should_terminate()` flag in order to receive this signal
+    /// that it should exit, however the target thread needs to check the
+    /// `thread::should_terminate()` flag (or block on e.g.
+    /// `thread::wait_for_termination()`) in order to receive this signal
     /// (and exit accordingly when it is set).
     pub fn request_termination(&self) {
         self.kill_switch.throw();
@@ -65,11 +195,40 @@ impl Thread {
         self.request_termination();
 
         // Wait for the other thread to exit
-        self.handle
-            .join()
-            .map_err(|e| format_err!(ThreadError, "{:?}", e))?;
+        self.wait()
+    }
+
+    /// Wait for this thread to finish without requesting that it terminate,
+    /// returning an error if it panicked or returned an error.
+    pub fn wait(self) -> Result<(), FrameworkError> {
+        self.handle.join().map_err(|e| {
+            format_err!(
+                ThreadError,
+                "thread '{}' panicked: {}",
+                self.name,
+                supervisor::panic_message(&*e)
+            )
+        })?
+    }
+
+    /// Spawn a new thread with the given (shared) status.
+    fn spawn_with_status<F>(name: Name, status: SharedStatus, f: F) -> Result<Self, FrameworkError>
+    where
+        F: FnOnce() -> Result<(), FrameworkError> + Send + 'static,
+    {
+        let kill_switch = Arc::new(KillSwitch::new());
+        let exit = ExitState::default();
+        let started = SystemTime::now();
+        let handle = spawn_thread(name.clone(), Arc::clone(&kill_switch), exit.clone(), f)?;
 
-        Ok(())
+        Ok(Self {
+            name,
+            kill_switch,
+            handle,
+            status,
+            started,
+            exit,
+        })
     }
 }
 
@@ -81,15 +240,71 @@ pub fn should_terminate() -> bool {
     kill_switch::is_thrown()
 }
 
+/// Block the currently running thread until it's requested to terminate or
+/// the given timeout elapses, returning whether or not termination has been
+/// requested.
+///
+/// Panics if called outside a thread spawned by `abscissa_core::Thread`.
+pub fn wait_for_termination(timeout: Duration) -> bool {
+    kill_switch::wait(timeout)
+}
+
+/// Sleep for the given duration, waking early if the currently running thread
+/// is requested to terminate.
+///
+/// Returns `true` if the full duration elapsed, or `false` if termination has
+/// been requested, e.g.:
+///
+/// ```ignore
+/// while thread::sleep_or_terminate(interval) {
+///     do_periodic_work();
+/// }
+/// ```
+///
+/// Panics if called outside a thread spawned by `abscissa_core::Thread`.
+pub fn sleep_or_terminate(duration: Duration) -> bool {
+    !kill_switch::wait(duration)
+}
+
+/// Get a receiver for the currently running thread which is disconnected
+/// when it's requested to terminate.
+///
+/// No messages are ever sent on this channel. It's intended to be used with
+/// `crossbeam_channel::select!` in order to block on other channels (or
+/// timers) while still exiting promptly when termination is requested.
+///
+/// Panics if called outside a thread spawned by `abscissa_core::Thread`.
+#[cfg(feature = "application")]
+pub fn termination_receiver() -> Receiver<()> {
+    kill_switch::receiver()
+}
+
 /// Spawn a thread
-fn spawn_thread<F>(name: Name, kill_switch: Arc<KillSwitch>, f: F) -> Result<JoinHandle, io::Error>
+///
+/// When tracing is enabled, the thread runs within a root span carrying its
+/// name, rather than inheriting the span of whichever thread spawned it.
+fn spawn_thread<F>(
+    name: Name,
+    kill_switch: Arc<KillSwitch>,
+    exit: ExitState,
+    f: F,
+) -> Result<JoinHandle, io::Error>
 where
-    F: FnOnce() + Send + 'static,
+    F: FnOnce() -> Result<(), FrameworkError> + Send + 'static,
 {
+    #[cfg(feature = "trace")]
+    let span = info_span!(parent: None, "thread", name = %name);
+
     // NOTE: `Name` ensures the absence of null bytes, which should prevent the
     // only condition under which this function could potentially panic.
     thread::Builder::new().name(name.into()).spawn(move || {
+        #[cfg(feature = "trace")]
+        let _enter = span.enter();
+
         kill_switch::set(kill_switch);
-        f()
+        let guard = PanicGuard(exit);
+        let result = f();
+        guard.0.returned(result.is_ok());
+        result
     })
 }
@@ -0,0 +1,62 @@
+//! Typed handles to threads which return results.
+
+use super::Name;
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Slot in which a thread stores the value it returns.
+pub(super) type ResultSlot<T> = Arc<Mutex<Option<T>>>;
+
+/// Handle to a thread spawned via [`Manager::spawn_result`] (or
+/// [`Manager::spawn_critical`]) which returns a value of type `T`.
+///
+/// The value can be obtained by passing the handle to [`Manager::wait`].
+///
+/// [`Manager::spawn_result`]: super::Manager::spawn_result
+/// [`Manager::spawn_critical`]: super::Manager::spawn_critical
+/// [`Manager::wait`]: super::Manager::wait
+pub struct Handle<T> {
+    /// Name of the thread
+    name: Name,
+
+    /// Value returned by the thread (once it has finished successfully)
+    result: ResultSlot<T>,
+
+    /// The thread's return type
+    return_type: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Create a new handle.
+    pub(super) fn new(name: Name, result: ResultSlot<T>) -> Self {
+        Self {
+            name,
+            result,
+            return_type: PhantomData,
+        }
+    }
+
+    /// Get the name of the thread.
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// Take the value returned by the thread (if it has finished successfully).
+    pub(super) fn take(self) -> Option<T> {
+        self.result
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,118 @@
+//! Introspection of managed threads.
+
+use super::Name;
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicU8, Ordering},
+    },
+    thread,
+    time::SystemTime,
+};
+
+/// Information about a managed thread.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Info {
+    /// Name of the thread
+    pub name: Name,
+
+    /// Time at which the thread was spawned
+    pub started: SystemTime,
+
+    /// Current state of the thread
+    pub state: State,
+
+    /// Number of times the thread has been restarted by its supervisor
+    pub restarts: u32,
+}
+
+/// States of a managed thread.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum State {
+    /// Thread is running
+    Running,
+
+    /// Thread has been requested to terminate, but is still running
+    Terminating,
+
+    /// Thread has exited normally
+    Exited,
+
+    /// Thread has exited by returning an error
+    Failed,
+
+    /// Thread has exited by panicking
+    Panicked,
+}
+
+impl State {
+    /// Has the thread exited?
+    pub fn is_exited(self) -> bool {
+        !matches!(self, State::Running | State::Terminating)
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            State::Running => "running",
+            State::Terminating => "terminating",
+            State::Exited => "exited",
+            State::Failed => "failed",
+            State::Panicked => "panicked",
+        })
+    }
+}
+
+/// How a thread exited (if it has), shared with the thread itself.
+#[derive(Clone, Debug, Default)]
+pub(super) struct ExitState(Arc<AtomicU8>);
+
+impl ExitState {
+    /// Thread is still running
+    const RUNNING: u8 = 0;
+
+    /// Thread exited normally
+    const EXITED: u8 = 1;
+
+    /// Thread returned an error
+    const FAILED: u8 = 2;
+
+    /// Thread panicked
+    const PANICKED: u8 = 3;
+
+    /// Get the state the thread exited with (if it has).
+    pub fn get(&self) -> Option<State> {
+        match self.0.load(Ordering::Acquire) {
+            Self::EXITED => Some(State::Exited),
+            Self::FAILED => Some(State::Failed),
+            Self::PANICKED => Some(State::Panicked),
+            _ => None,
+        }
+    }
+
+    /// Record that the thread has returned, successfully or otherwise.
+    pub fn returned(&self, success: bool) {
+        let state = if success { Self::EXITED } else { Self::FAILED };
+        self.0.store(state, Ordering::Release);
+    }
+}
+
+/// Guard which records that a thread has panicked if it's dropped while
+/// unwinding.
+#[derive(Debug)]
+pub(super) struct PanicGuard(pub ExitState);
+
+impl Drop for PanicGuard {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            let _ = self.0.0.compare_exchange(
+                ExitState::RUNNING,
+                ExitState::PANICKED,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            );
+        }
+    }
+}
@@ -1,40 +1,90 @@
 //! Kill switches are a cooperative approach to requesting a thread terminate,
 //! by setting a flag in one thread that another thread can periodically check
 //! in order to determine if it should exit.
+//!
+//! Threads can also block until the kill switch is thrown, or (with the
+//! `application` feature) wait on a channel which is disconnected when it's
+//! thrown.
 
 thread_local! {
     /// Boolean flag signaling to a thread to terminate
     static KILL_SWITCH: RefCell<Option<Arc<KillSwitch>>> = const { RefCell::new(None) };
 }
 
+#[cfg(feature = "application")]
+use crossbeam_channel::{Receiver, Sender};
 use std::{
     cell::RefCell,
-    sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
-    },
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::Duration,
 };
 
 /// Thread kill switch.
 ///
 /// This is a signal that the thread should terminate.
 #[derive(Debug)]
-pub(super) struct KillSwitch(AtomicBool);
+pub(super) struct KillSwitch {
+    /// Has the kill switch been thrown?
+    thrown: Mutex<bool>,
+
+    /// Condition variable notified when the kill switch is thrown
+    condvar: Condvar,
+
+    /// Sender which is dropped when the kill switch is thrown, disconnecting
+    /// the receiver below
+    #[cfg(feature = "application")]
+    sender: Mutex<Option<Sender<()>>>,
+
+    /// Receiver which is disconnected when the kill switch is thrown
+    #[cfg(feature = "application")]
+    receiver: Receiver<()>,
+}
 
 impl KillSwitch {
     /// Create a new kill switch
     pub fn new() -> KillSwitch {
-        KillSwitch(AtomicBool::new(false))
+        #[cfg(feature = "application")]
+        let (sender, receiver) = crossbeam_channel::bounded(0);
+
+        KillSwitch {
+            thrown: Mutex::new(false),
+            condvar: Condvar::new(),
+            #[cfg(feature = "application")]
+            sender: Mutex::new(Some(sender)),
+            #[cfg(feature = "application")]
+            receiver,
+        }
     }
 
     /// Throw the kill switch, indicating it's time to terminate
     pub fn throw(&self) {
-        self.0.store(true, Ordering::Relaxed);
+        *lock(&self.thrown) = true;
+        self.condvar.notify_all();
+
+        #[cfg(feature = "application")]
+        lock(&self.sender).take();
     }
 
     /// Has the kill switch been thrown?
     pub fn is_thrown(&self) -> bool {
-        self.0.load(Ordering::Relaxed)
+        *lock(&self.thrown)
+    }
+
+    /// Block until the kill switch is thrown or the timeout elapses,
+    /// returning whether or not it has been thrown.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let (thrown, _) = self
+            .condvar
+            .wait_timeout_while(lock(&self.thrown), timeout, |thrown| !*thrown)
+            .unwrap_or_else(PoisonError::into_inner);
+
+        *thrown
+    }
+
+    /// Get a receiver which is disconnected when the kill switch is thrown.
+    #[cfg(feature = "application")]
+    pub fn receiver(&self) -> Receiver<()> {
+        self.receiver.clone()
     }
 }
 
@@ -42,15 +92,46 @@ impl KillSwitch {
 ///
 /// Panics if no kill switch is configured for the current thread.
 pub(super) fn is_thrown() -> bool {
-    KILL_SWITCH.with(|ks| {
-        ks.borrow()
-            .as_ref()
-            .expect("no kill switch configured for current thread")
-            .is_thrown()
-    })
+    with(KillSwitch::is_thrown)
+}
+
+/// Block until the kill switch for this thread is thrown or the timeout
+/// elapses, returning whether or not it has been thrown.
+///
+/// Panics if no kill switch is configured for the current thread.
+pub(super) fn wait(timeout: Duration) -> bool {
+    with(|kill_switch| kill_switch.wait(timeout))
+}
+
+/// Get a receiver which is disconnected when the kill switch for this thread
+/// is thrown.
+///
+/// Panics if no kill switch is configured for the current thread.
+#[cfg(feature = "application")]
+pub(super) fn receiver() -> Receiver<()> {
+    with(KillSwitch::receiver)
 }
 
 /// Set the kill switch value for the current thread
 pub(super) fn set(kill_switch: Arc<KillSwitch>) {
     KILL_SWITCH.with(|ks| *ks.borrow_mut() = Some(kill_switch));
 }
+
+/// Invoke the given function with the kill switch for the current thread.
+///
+/// Panics if no kill switch is configured for the current thread.
+fn with<T>(f: impl FnOnce(&KillSwitch) -> T) -> T {
+    let kill_switch = KILL_SWITCH.with(|ks| {
+        ks.borrow()
+            .as_ref()
+            .map(Arc::clone)
+            .expect("no kill switch configured for current thread")
+    });
+
+    f(&kill_switch)
+}
+
+/// Lock the given mutex, ignoring poisoning.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
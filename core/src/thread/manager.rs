@@ -1,8 +1,22 @@
 //! Thread manager.
 
-use super::{Name, Thread};
-use crate::{FrameworkError, FrameworkErrorKind::ThreadError, Map};
-use std::{convert::TryInto, sync};
+use super::{
+    Handle, Info, Name, Thread,
+    supervisor::{self, EscalationHandler, Failure, Policy, Status},
+};
+use crate::{FrameworkError, FrameworkErrorKind::ThreadError, Map, error::BoxError};
+use std::{
+    cmp,
+    convert::TryInto,
+    fmt, mem,
+    sync::{self, Arc, Mutex, PoisonError, mpsc},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Interval at which to check whether threads have exited when joining them
+/// with a timeout.
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Reader guard for the thread manager.
 pub type Reader<'a> = sync::RwLockReadGuard<'a, Manager>;
@@ -12,9 +26,16 @@ pub type Writer<'a> = sync::RwLockWriteGuard<'a, Manager>;
 
 /// Thread manager that tracks threads spawned by the application and handles
 /// shutting them down.
-#[derive(Debug, Default)]
 pub struct Manager {
+    /// Threads spawned by the manager
     threads: Map<Name, Thread>,
+
+    /// Handler for failures of threads with the [`Policy::Escalate`] policy
+    /// and critical threads
+    escalation: EscalationHandler,
+
+    /// Failures queued by the default escalation handler
+    escalated: Mutex<mpsc::Receiver<(Name, Failure)>>,
 }
 
 impl Manager {
@@ -23,40 +44,296 @@ impl Manager {
     where
         F: FnOnce() + Send + 'static,
     {
-        // TODO(tarcieri): propagate underlying error (after error handling refactor)
-        let name = name
-            .try_into()
-            .ok()
-            .ok_or_else(|| format_err!(ThreadError, "invalid thread name"))?;
+        let name = self.new_name(name)?;
+        let thread = Thread::spawn(name.clone(), f)?;
+        self.threads.insert(name, thread);
 
-        if self.threads.contains_key(&name) {
-            fail!(ThreadError, "duplicate name: {}", name);
-        }
+        Ok(())
+    }
 
-        let thread = Thread::spawn(name.clone(), f)?;
+    /// Spawn a thread within the thread manager which returns a result,
+    /// returning a typed handle which can be used to [`wait`] for its value.
+    ///
+    /// If the thread returns an error, it's recorded as the thread's most
+    /// recent failure and surfaced when the thread is joined.
+    ///
+    /// [`wait`]: Manager::wait
+    pub fn spawn_result<T, E, F>(
+        &mut self,
+        name: impl TryInto<Name>,
+        f: F,
+    ) -> Result<Handle<T>, FrameworkError>
+    where
+        T: Send + 'static,
+        E: Into<BoxError>,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+    {
+        self.spawn_result_with(name, None, f)
+    }
+
+    /// Spawn a critical thread within the thread manager which returns a
+    /// result, escalating its failure (i.e. an error or panic) to the
+    /// escalation handler, which crashes applications started with
+    /// `Application::run` as soon as the thread fails.
+    ///
+    /// See [`Manager::spawn_result`] for more information.
+    pub fn spawn_critical<T, E, F>(
+        &mut self,
+        name: impl TryInto<Name>,
+        f: F,
+    ) -> Result<Handle<T>, FrameworkError>
+    where
+        T: Send + 'static,
+        E: Into<BoxError>,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+    {
+        let escalation = Arc::clone(&self.escalation);
+        self.spawn_result_with(name, Some(escalation), f)
+    }
+
+    /// Spawn a supervised thread within the thread manager, which is
+    /// restarted (or has its failure escalated) according to the given
+    /// policy if it panics.
+    pub fn spawn_supervised<F>(
+        &mut self,
+        name: impl TryInto<Name>,
+        policy: Policy,
+        f: F,
+    ) -> Result<(), FrameworkError>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let name = self.new_name(name)?;
+        let thread =
+            Thread::spawn_supervised(name.clone(), policy, Arc::clone(&self.escalation), f)?;
         self.threads.insert(name, thread);
 
         Ok(())
     }
 
-    /// Signal all running threads to terminate and then join them
-    pub fn join(&mut self) -> Result<(), FrameworkError> {
-        let mut names = Vec::with_capacity(self.threads.len());
+    /// Get information about all of the threads tracked by the manager,
+    /// ordered by name.
+    ///
+    /// Threads remain tracked until they're joined, so this includes threads
+    /// which have exited.
+    pub fn list(&self) -> Vec<Info> {
+        self.threads.values().map(Thread::info).collect()
+    }
 
+    /// Get information about the thread with the given name.
+    pub fn info(&self, name: &Name) -> Option<Info> {
+        self.threads.get(name).map(Thread::info)
+    }
+
+    /// Get the supervision status of the thread with the given name.
+    pub fn status(&self, name: &Name) -> Option<Status> {
+        self.threads.get(name).map(Thread::status)
+    }
+
+    /// Request that the thread with the given name terminate, returning
+    /// `false` if there's no such thread.
+    pub fn request_termination(&self, name: &Name) -> bool {
+        self.threads
+            .get(name)
+            .map(Thread::request_termination)
+            .is_some()
+    }
+
+    /// Set the handler invoked when a thread with the [`Policy::Escalate`]
+    /// policy (or a critical thread) fails.
+    ///
+    /// By default, the failure is logged and queued for [`Manager::escalated`].
+    /// `Application::run` replaces this with a handler which shuts down the
+    /// application with `Shutdown::Crash`.
+    ///
+    /// Only affects threads spawned after the handler is set.
+    pub fn set_escalation_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&Name, &Failure) + Send + Sync + 'static,
+    {
+        self.escalation = Arc::new(handler);
+    }
+
+    /// Take the oldest failure queued by the default escalation handler, if
+    /// any thread with the [`Policy::Escalate`] policy (or critical thread)
+    /// has failed.
+    ///
+    /// This is only useful for thread managers which aren't owned by an
+    /// application started with `Application::run`, which shuts down as
+    /// soon as a failure is escalated instead (see
+    /// [`Manager::set_escalation_handler`]).
+    pub fn escalated(&self) -> Option<(Name, Failure)> {
+        self.escalated
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_recv()
+            .ok()
+    }
+
+    /// Wait for the thread with the given handle to finish (without requesting
+    /// that it terminate), returning the value it returned.
+    ///
+    /// Returns an error tagged with the thread's name if it returned an error
+    /// or panicked.
+    pub fn wait<T>(&mut self, handle: Handle<T>) -> Result<T, FrameworkError> {
+        let thread = self
+            .threads
+            .remove(handle.name())
+            .ok_or_else(|| format_err!(ThreadError, "no such thread: {}", handle.name()))?;
+
+        thread.wait()?;
+
+        let name = handle.name().clone();
+        handle
+            .take()
+            .ok_or_else(|| format_err!(ThreadError, "thread '{}' returned no value", name).into())
+    }
+
+    /// Signal all running threads to terminate and then join them.
+    ///
+    /// Every thread is joined even if some of them failed, in which case the
+    /// returned error describes all of the failures.
+    pub fn join(&mut self) -> Result<(), FrameworkError> {
         // Send termination request in advance prior to joining
-        for (name, thread) in self.threads.iter() {
-            names.push(name.clone());
+        for thread in self.threads.values() {
             thread.request_termination();
         }
 
-        // TODO(tarcieri): use `BTreeMap::into_values` when stable
-        // See: <https://github.com/rust-lang/rust/issues/75294>
-        for name in names.into_iter() {
-            if let Some(thread) = self.threads.remove(&name) {
-                thread.join()?;
+        join_all(mem::take(&mut self.threads).into_values())
+    }
+
+    /// Signal all running threads to terminate and then join the ones which
+    /// exit within the given timeout.
+    ///
+    /// Returns the names of the threads which failed to exit in time, which
+    /// are also logged as warnings. These threads are left running and remain
+    /// tracked by the manager. The calling thread (if it's managed) is never
+    /// waited on, as joining it would deadlock.
+    ///
+    /// Returns an error describing the failures of any of the joined threads.
+    pub fn join_timeout(&mut self, timeout: Duration) -> Result<Vec<Name>, FrameworkError> {
+        let deadline = Instant::now() + timeout;
+
+        for thread in self.threads.values() {
+            thread.request_termination();
+        }
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline
+                || self
+                    .threads
+                    .values()
+                    .all(|t| t.is_finished() || t.is_current())
+            {
+                break;
             }
+
+            thread::sleep(cmp::min(JOIN_POLL_INTERVAL, deadline - now));
         }
 
-        Ok(())
+        let (finished, running) = mem::take(&mut self.threads)
+            .into_iter()
+            .partition::<Map<_, _>, _>(|(_, thread)| thread.is_finished());
+
+        self.threads = running;
+        let result = join_all(finished.into_values());
+
+        let stuck = self
+            .threads
+            .iter()
+            .filter(|(_, thread)| !thread.is_current())
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "trace")]
+        for name in &stuck {
+            warn!(thread = %name, "thread '{}' did not exit within {:?}", name, timeout);
+        }
+
+        result.map(|()| stuck)
+    }
+
+    /// Spawn a thread which returns a result, escalating its failure to the
+    /// given handler (if any).
+    fn spawn_result_with<T, E, F>(
+        &mut self,
+        name: impl TryInto<Name>,
+        escalation: Option<EscalationHandler>,
+        f: F,
+    ) -> Result<Handle<T>, FrameworkError>
+    where
+        T: Send + 'static,
+        E: Into<BoxError>,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+    {
+        let name = self.new_name(name)?;
+        let (thread, handle) = Thread::spawn_result(name.clone(), escalation, f)?;
+        self.threads.insert(name, thread);
+
+        Ok(handle)
+    }
+
+    /// Validate the name of a new thread, ensuring it's unique.
+    fn new_name(&self, name: impl TryInto<Name>) -> Result<Name, FrameworkError> {
+        let name = parse_name(name)?;
+
+        if self.threads.contains_key(&name) {
+            fail!(ThreadError, "duplicate name: {}", name);
+        }
+
+        Ok(name)
+    }
+}
+
+/// Parse the name of a thread (or pool of threads).
+pub(super) fn parse_name(name: impl TryInto<Name>) -> Result<Name, FrameworkError> {
+    // TODO(tarcieri): propagate underlying error (after error handling refactor)
+    name.try_into()
+        .ok()
+        .ok_or_else(|| format_err!(ThreadError, "invalid thread name").into())
+}
+
+/// Join the given threads, combining the errors of any which failed.
+fn join_all(threads: impl IntoIterator<Item = Thread>) -> Result<(), FrameworkError> {
+    let mut errors = threads
+        .into_iter()
+        .filter_map(|thread| thread.join().err())
+        .collect::<Vec<_>>();
+
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        n => fail!(
+            ThreadError,
+            "{} threads failed: {}",
+            n,
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+    }
+}
+
+impl Default for Manager {
+    fn default() -> Self {
+        let (sender, escalated) = mpsc::channel();
+
+        Self {
+            threads: Map::default(),
+            escalation: supervisor::default_escalation_handler(sender),
+            escalated: Mutex::new(escalated),
+        }
+    }
+}
+
+impl fmt::Debug for Manager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Manager")
+            .field("threads", &self.threads)
+            .finish_non_exhaustive()
     }
 }
@@ -0,0 +1,285 @@
+//! Bounded worker pools.
+//!
+//! A [`Pool`] spawns a fixed number of worker threads within a thread
+//! [`Manager`], named `<pool name>/0` through `<pool name>/<N-1>`, which run
+//! jobs submitted to a bounded queue. Submitting a job to a full queue either
+//! blocks ([`Pool::execute`]) or returns an error ([`Pool::try_execute`]),
+//! providing backpressure.
+//!
+//! Pools are shut down with [`Pool::shutdown`], typically from the
+//! `before_shutdown` callback of the component which owns them:
+//!
+//! - [`Shutdown::Graceful`]: workers drain the queue before exiting
+//! - [`Shutdown::Forced`] / [`Shutdown::Crash`]: queued jobs are dropped and
+//!   workers are requested to terminate, exiting once their current job
+//!   completes
+//!
+//! Workers also exit (dropping queued jobs) when the thread manager requests
+//! that they terminate, e.g. via [`Manager::join`].
+
+use super::{Handle, Manager, Name, manager};
+use crate::{FrameworkError, FrameworkErrorKind::ThreadError, shutdown::Shutdown};
+use crossbeam_channel::{Receiver, Sender, TrySendError, select};
+use std::{
+    any::Any,
+    convert::Infallible,
+    fmt, mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default capacity of a pool's job queue.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+/// Jobs run by a [`Pool`].
+pub type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Worker pool configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct Config {
+    /// Number of worker threads
+    pub workers: usize,
+
+    /// Maximum number of jobs waiting in the queue (at least 1)
+    pub queue_capacity: usize,
+}
+
+impl Config {
+    /// Create a configuration with the given number of workers.
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Config {
+    /// Defaults to one worker per available CPU.
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, usize::from),
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+        }
+    }
+}
+
+/// Pool of worker threads which run jobs from a bounded queue.
+pub struct Pool {
+    /// Name of the pool
+    name: Name,
+
+    /// Names of the worker threads
+    worker_names: Vec<Name>,
+
+    /// Handles to the worker threads which haven't yet been shut down
+    workers: Mutex<Vec<Handle<()>>>,
+
+    /// Sender for submitting jobs to the queue (until shut down)
+    sender: Mutex<Option<Sender<Job>>>,
+
+    /// Receiver for the queue, used to drop queued jobs on forced shutdown
+    receiver: Receiver<Job>,
+
+    /// Number of jobs which have panicked
+    panicked: Arc<AtomicUsize>,
+}
+
+impl Pool {
+    /// Spawn a worker pool with the given name and configuration within the
+    /// given thread manager.
+    pub fn spawn(
+        threads: &mut Manager,
+        name: impl TryInto<Name>,
+        config: &Config,
+    ) -> Result<Self, FrameworkError> {
+        let name = manager::parse_name(name)?;
+
+        ensure!(
+            config.workers > 0,
+            ThreadError,
+            "pool '{}' must have at least one worker",
+            name
+        );
+
+        ensure!(
+            config.queue_capacity > 0,
+            ThreadError,
+            "pool '{}' must have a queue capacity of at least one job",
+            name
+        );
+
+        let (sender, receiver) = crossbeam_channel::bounded(config.queue_capacity);
+        let panicked = Arc::new(AtomicUsize::new(0));
+        let mut workers = Vec::with_capacity(config.workers);
+
+        for n in 0..config.workers {
+            let worker_name = Name::new(format!("{}/{}", name, n))?;
+            let jobs = receiver.clone();
+            let panicked = Arc::clone(&panicked);
+            workers.push(threads.spawn_result(worker_name, move || run_worker(&jobs, &panicked))?);
+        }
+
+        Ok(Self {
+            name,
+            worker_names: workers.iter().map(|worker| worker.name().clone()).collect(),
+            workers: Mutex::new(workers),
+            sender: Mutex::new(Some(sender)),
+            receiver,
+            panicked,
+        })
+    }
+
+    /// Get the name of this pool.
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// Get the names of this pool's worker threads.
+    pub fn workers(&self) -> &[Name] {
+        &self.worker_names
+    }
+
+    /// Number of jobs waiting in the queue.
+    pub fn queued(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Number of jobs which have panicked.
+    pub fn panicked(&self) -> usize {
+        self.panicked.load(Ordering::Relaxed)
+    }
+
+    /// Submit a job to the pool, blocking until there's room in the queue.
+    pub fn execute<F>(&self, job: F) -> Result<(), FrameworkError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender()?
+            .send(Box::new(job))
+            .map_err(|_| format_err!(ThreadError, "pool '{}' has shut down", self.name).into())
+    }
+
+    /// Submit a job to the pool, returning an error if the queue is full.
+    pub fn try_execute<F>(&self, job: F) -> Result<(), FrameworkError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender()?
+            .try_send(Box::new(job))
+            .map_err(|e| match e {
+                TrySendError::Full(_) => {
+                    format_err!(ThreadError, "pool '{}' queue is full", self.name)
+                }
+                TrySendError::Disconnected(_) => {
+                    format_err!(ThreadError, "pool '{}' has shut down", self.name)
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Shut down this pool, waiting for its workers to exit.
+    ///
+    /// Graceful shutdowns run all queued jobs first, whereas forced (and
+    /// crash) shutdowns drop them and request that the workers terminate.
+    ///
+    /// Jobs can no longer be submitted once the pool has been shut down.
+    pub fn shutdown(
+        &self,
+        threads: &mut Manager,
+        shutdown: Shutdown,
+    ) -> Result<(), FrameworkError> {
+        let workers = mem::take(&mut *lock(&self.workers));
+
+        if shutdown != Shutdown::Graceful {
+            while self.receiver.try_recv().is_ok() {}
+
+            for worker in &workers {
+                threads.request_termination(worker.name());
+            }
+        }
+
+        // Disconnect the queue, signaling workers to exit once it's empty
+        lock(&self.sender).take();
+
+        let mut result = Ok(());
+
+        for worker in workers {
+            result = result.and(threads.wait(worker));
+        }
+
+        result
+    }
+
+    /// Get a sender for submitting jobs to the queue.
+    fn sender(&self) -> Result<Sender<Job>, FrameworkError> {
+        lock(&self.sender)
+            .clone()
+            .ok_or_else(|| format_err!(ThreadError, "pool '{}' has shut down", self.name).into())
+    }
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("name", &self.name)
+            .field("workers", &self.worker_names.len())
+            .field("queued", &self.queued())
+            .field("panicked", &self.panicked())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Lock the given mutex, ignoring poisoning.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Run jobs from the given queue until it's disconnected or the current
+/// thread is requested to terminate.
+///
+/// Panicking jobs are logged and counted, but don't take down the worker.
+fn run_worker(jobs: &Receiver<Job>, panicked: &AtomicUsize) -> Result<(), Infallible> {
+    let terminate = super::termination_receiver();
+
+    loop {
+        select! {
+            recv(jobs) -> job => match job {
+                Ok(job) => {
+                    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(job)) {
+                        report_panic(&*payload, panicked);
+                    }
+                }
+                Err(_) => break,
+            },
+            recv(terminate) -> _ => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Count (and log) a panicking job.
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+fn report_panic(payload: &(dyn Any + Send), panicked: &AtomicUsize) {
+    panicked.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "trace")]
+    error!(
+        "pool job panicked: {}",
+        super::supervisor::panic_message(payload)
+    );
+}
@@ -0,0 +1,236 @@
+//! Supervision of managed threads.
+//!
+//! Threads spawned via [`Manager::spawn_supervised`] are run under a
+//! [`Policy`] which determines what happens when they panic: they can be left
+//! stopped, restarted with exponential backoff, or the failure can be
+//! escalated (e.g. to shut down the application).
+//!
+//! The panic message of the most recent failure, along with the number of
+//! times the thread has been restarted, is recorded in the thread's
+//! [`Status`], which is available via [`Manager::status`].
+//!
+//! [`Manager::spawn_supervised`]: super::Manager::spawn_supervised
+//! [`Manager::status`]: super::Manager::status
+
+use super::Name;
+use std::{
+    any::Any,
+    cmp, fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, MutexGuard, PoisonError, mpsc::Sender},
+    time::{Duration, SystemTime},
+};
+
+/// Handler invoked when a thread with the [`Policy::Escalate`] policy (or a
+/// critical thread) fails.
+pub type EscalationHandler = Arc<dyn Fn(&Name, &Failure) + Send + Sync>;
+
+/// Restart policies for supervised threads.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Policy {
+    /// Never restart the thread: failures are reported when it's joined.
+    #[default]
+    Never,
+
+    /// Restart the thread after a delay which doubles after each restart.
+    Backoff {
+        /// Delay before the first restart
+        initial: Duration,
+
+        /// Maximum delay between restarts
+        max: Duration,
+
+        /// Maximum number of restarts (if any), after which the thread is
+        /// left stopped
+        max_restarts: Option<u32>,
+    },
+
+    /// Escalate the failure to the thread manager's escalation handler,
+    /// which crashes applications started with `Application::run` (see
+    /// [`Manager::set_escalation_handler`]).
+    ///
+    /// [`Manager::set_escalation_handler`]: super::Manager::set_escalation_handler
+    Escalate,
+}
+
+impl Policy {
+    /// Restart with exponential backoff, starting at the given delay and
+    /// doubling up to the given maximum, with no limit on restarts.
+    pub fn backoff(initial: Duration, max: Duration) -> Self {
+        Policy::Backoff {
+            initial,
+            max,
+            max_restarts: None,
+        }
+    }
+}
+
+/// Failure of a managed thread (i.e. a panic or returned error).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Failure {
+    /// Panic (or error) message
+    pub message: String,
+
+    /// Time at which the failure occurred
+    pub time: SystemTime,
+}
+
+impl Failure {
+    /// Create a failure from a panic payload.
+    pub(super) fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        Self {
+            message: panic_message(payload),
+            time: SystemTime::now(),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Supervision status of a managed thread.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Status {
+    /// Number of times the thread has been restarted
+    pub restarts: u32,
+
+    /// Most recent failure of the thread (if any)
+    pub last_failure: Option<Failure>,
+}
+
+/// Shared status of a running thread.
+pub(super) type SharedStatus = Arc<Mutex<Status>>;
+
+/// Default escalation handler: logs the failure and queues it to be picked
+/// up by the thread which shuts down the application.
+pub(super) fn default_escalation_handler(escalated: Sender<(Name, Failure)>) -> EscalationHandler {
+    Arc::new(move |name, failure| {
+        #[cfg(feature = "trace")]
+        error!(thread = %name, "thread '{}' failed: {}", name, failure);
+
+        // The receiver is owned by the thread manager, which outlives its threads
+        let _ = escalated.send((name.clone(), failure.clone()));
+    })
+}
+
+/// Run the given function once, recording its failure (if any) and
+/// escalating it to the given handler (if any) before resuming the panic.
+pub(super) fn run_once<T, F>(
+    name: &Name,
+    status: &SharedStatus,
+    escalation: Option<&EscalationHandler>,
+    f: F,
+) -> T
+where
+    F: FnOnce() -> T,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            let (_, failure) = record_failure(status, &*payload);
+
+            if let Some(escalation) = escalation {
+                escalation(name, &failure);
+            }
+
+            panic::resume_unwind(payload)
+        }
+    }
+}
+
+/// Record an error returned by a thread as its most recent failure,
+/// escalating it to the given handler (if any).
+pub(super) fn report_error(
+    name: &Name,
+    status: &SharedStatus,
+    escalation: Option<&EscalationHandler>,
+    message: String,
+) -> Failure {
+    let failure = Failure {
+        message,
+        time: SystemTime::now(),
+    };
+
+    lock(status).last_failure = Some(failure.clone());
+
+    if let Some(escalation) = escalation {
+        escalation(name, &failure);
+    }
+
+    failure
+}
+
+/// Run the given function under the given supervision policy.
+pub(super) fn supervise<F>(
+    name: &Name,
+    policy: Policy,
+    status: &SharedStatus,
+    escalation: &EscalationHandler,
+    f: F,
+) where
+    F: Fn(),
+{
+    loop {
+        let payload = match panic::catch_unwind(AssertUnwindSafe(&f)) {
+            Ok(()) => return,
+            Err(payload) => payload,
+        };
+
+        let (restarts, failure) = record_failure(status, &*payload);
+
+        match policy {
+            Policy::Never => panic::resume_unwind(payload),
+            Policy::Escalate => {
+                escalation(name, &failure);
+                panic::resume_unwind(payload)
+            }
+            Policy::Backoff {
+                initial,
+                max,
+                max_restarts,
+            } => {
+                if max_restarts.is_some_and(|max_restarts| restarts >= max_restarts) {
+                    panic::resume_unwind(payload);
+                }
+
+                let delay = initial
+                    .checked_mul(1 << cmp::min(restarts, 31))
+                    .map_or(max, |delay| cmp::min(delay, max));
+
+                if !super::sleep_or_terminate(delay) {
+                    return;
+                }
+
+                lock(status).restarts += 1;
+            }
+        }
+    }
+}
+
+/// Get the message from a panic payload.
+pub(super) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+/// Lock the given status, ignoring poisoning.
+pub(super) fn lock(status: &SharedStatus) -> MutexGuard<'_, Status> {
+    status.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Record a failure in the given status, returning the number of restarts
+/// so far along with the failure.
+fn record_failure(status: &SharedStatus, payload: &(dyn Any + Send)) -> (u32, Failure) {
+    let failure = Failure::from_panic(payload);
+    let mut status = lock(status);
+    status.last_failure = Some(failure.clone());
+    (status.restarts, failure)
+}
@@ -3,7 +3,23 @@
 #[cfg(feature = "application")]
 pub mod component;
 mod config;
+#[cfg_attr(not(unix), allow(dead_code))]
+pub(crate) mod fields;
+pub mod file;
+mod format;
+#[cfg(unix)]
+pub mod journald;
+pub mod sink;
+#[cfg(unix)]
+pub mod syslog;
 
 #[cfg(feature = "application")]
 pub use self::component::Tracing;
-pub use self::config::Config;
+pub use self::{
+    config::{Config, Overrides, SpanEvents},
+    file::{RollingFile, Rotation},
+    format::Format,
+};
+
+#[cfg(unix)]
+pub use self::{journald::Journald, syslog::Syslog};
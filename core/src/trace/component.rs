@@ -1,61 +1,196 @@
 //! Abscissa tracing component
 
-// TODO(tarcieri): logfile support?
+mod sinks;
 
+use self::sinks::{Sink, Sinks};
+use super::{
+    config::{Config, Overrides},
+    file::RollingFile,
+};
+use crate::{
+    Application, Component, FrameworkError, FrameworkErrorKind, Injectable, Shutdown,
+    component::ConfigSection, terminal::ColorChoice,
+};
+use std::fmt;
 use tracing_log::LogTracer;
-use tracing_subscriber::{EnvFilter, FmtSubscriber, fmt::Formatter, reload::Handle};
+use tracing_subscriber::{Registry, layer::SubscriberExt};
 
-use super::config::Config;
-use crate::{Component, FrameworkError, FrameworkErrorKind, terminal::ColorChoice};
-
-/// Abscissa component for initializing the `tracing` subsystem
-#[derive(Component, Debug)]
-#[component(core)]
+/// Abscissa component for initializing the `tracing` subsystem.
+///
+/// Log output is written to one or more named sinks, each with their own
+/// filter and format (see the [`sink`](super::sink) module).
+///
+/// This component is configured by the `[tracing]` section of the
+/// application's configuration file (if present), which replaces the
+/// [`Config`] it was created with once the configuration is loaded. Any
+/// [`Overrides`] given on the command line are applied on top of it.
+#[derive(Injectable)]
+#[component(core, config = "tracing")]
 pub struct Tracing {
-    filter_handle: Handle<EnvFilter, Formatter>,
+    /// Layer which writes events to the sinks
+    sinks: Sinks,
+
+    /// Default filter, used by sinks which don't have their own
+    default_filter: String,
+
+    /// Color choice for sinks which write to the terminal
+    color_choice: ColorChoice,
+
+    /// Command-line overrides applied to every configuration
+    overrides: Overrides,
 }
 
 impl Tracing {
     /// Create a new [`Tracing`] component from the given [`Config`].
     pub fn new(config: Config, color_choice: ColorChoice) -> Result<Self, FrameworkError> {
+        Self::with_overrides(config, Overrides::default(), color_choice)
+    }
+
+    /// Create a new [`Tracing`] component from the given [`Config`], with
+    /// the given [`Overrides`] applied to it and any subsequent configuration.
+    pub fn with_overrides(
+        config: Config,
+        overrides: Overrides,
+        color_choice: ColorChoice,
+    ) -> Result<Self, FrameworkError> {
         // Configure log/tracing interoperability by setting a `LogTracer` as
         // the global logger for the log crate, which converts all log events
         // into tracing events.
         LogTracer::init().map_err(|e| FrameworkErrorKind::ComponentError.context(e))?;
 
-        // Construct a tracing subscriber with the supplied filter and enable reloading.
-        let builder = FmtSubscriber::builder()
-            .with_ansi(match color_choice {
-                ColorChoice::Always => true,
-                ColorChoice::AlwaysAnsi => true,
-                ColorChoice::Auto => true,
-                ColorChoice::Never => false,
-            })
-            .with_env_filter(config.filter)
-            .with_filter_reloading();
-        let filter_handle = builder.reload_handle();
-        let subscriber = builder.finish();
-
-        // Now set it as the global tracing subscriber and save the handle.
+        let mut tracing = Self {
+            sinks: Sinks::default(),
+            default_filter: String::new(),
+            color_choice,
+            overrides,
+        };
+
+        tracing.reconfigure(config)?;
+
+        // Now set it as the global tracing subscriber.
+        let subscriber = Registry::default().with(tracing.sinks.clone());
         tracing::subscriber::set_global_default(subscriber)
             .map_err(|e| FrameworkErrorKind::ComponentError.context(e))?;
 
-        Ok(Self { filter_handle })
+        Ok(tracing)
     }
 
-    /// Return the currently-active tracing filter.
+    /// Return the default tracing filter, used by sinks which don't have
+    /// their own.
     pub fn filter(&self) -> String {
-        self.filter_handle
-            .with_current(|filter| filter.to_string())
-            .expect("the subscriber is not dropped before the component is")
+        self.default_filter.clone()
     }
 
-    /// Reload the currently-active filter with the supplied value.
+    /// Reload the default filter with the supplied value, updating every
+    /// sink which doesn't have its own filter.
     ///
     /// This can be used to provide a dynamic tracing filter endpoint.
-    pub fn reload_filter(&mut self, filter: impl Into<EnvFilter>) {
-        self.filter_handle
-            .reload(filter)
-            .expect("the subscriber is not dropped before the component is");
+    pub fn reload_filter(&mut self, filter: impl Into<String>) -> Result<(), FrameworkError> {
+        let filter = filter.into();
+        self.sinks
+            .reload_filters(&filter, |sink| sink.default_filter)?;
+        self.default_filter = filter;
+        Ok(())
+    }
+
+    /// Get the names of the active sinks.
+    pub fn sinks(&self) -> Vec<String> {
+        self.sinks
+            .read()
+            .iter()
+            .map(|sink| sink.name.clone())
+            .collect()
+    }
+
+    /// Return the filter of the sink with the given name (if it exists).
+    pub fn sink_filter(&self, name: &str) -> Option<String> {
+        self.sinks
+            .read()
+            .iter()
+            .find(|sink| sink.name == name)
+            .map(|sink| sink.filter.to_string())
+    }
+
+    /// Reload the filter of the sink with the given name.
+    pub fn reload_sink_filter(&mut self, name: &str, filter: &str) -> Result<(), FrameworkError> {
+        let updated = self
+            .sinks
+            .reload_filters(filter, |sink| sink.name == name)?;
+        ensure!(
+            updated > 0,
+            FrameworkErrorKind::ComponentError,
+            "no such tracing sink: {}",
+            name
+        );
+        Ok(())
+    }
+
+    /// Apply the given configuration, replacing the currently-active sinks.
+    ///
+    /// Command-line overrides take precedence over the given configuration.
+    pub fn reconfigure(&mut self, config: Config) -> Result<(), FrameworkError> {
+        let config = self.overrides.apply(config);
+        let sinks = config
+            .all_sinks()
+            .iter()
+            .map(|(name, sink)| Sink::open(name, sink, &config, self.color_choice))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.flush()?;
+        self.sinks.replace(sinks);
+        self.default_filter = config.filter();
+        Ok(())
+    }
+
+    /// Get the log file written to by the sink with the given name (if any).
+    pub fn file(&self, sink: &str) -> Option<RollingFile> {
+        self.sinks
+            .read()
+            .iter()
+            .find(|s| s.name == sink)
+            .and_then(|s| s.file.clone())
+    }
+
+    /// Flush buffered log file output.
+    pub fn flush(&self) -> Result<(), FrameworkError> {
+        for file in self
+            .sinks
+            .read()
+            .iter()
+            .filter_map(|sink| sink.file.as_ref())
+        {
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<A> Component<A> for Tracing
+where
+    A: Application,
+{
+    /// Flush buffered log file output.
+    fn before_shutdown(&self, _kind: Shutdown) -> Result<(), FrameworkError> {
+        self.flush()
+    }
+}
+
+impl ConfigSection for Tracing {
+    type Config = Config;
+
+    fn after_config_section(&mut self, config: Config) -> Result<(), FrameworkError> {
+        self.reconfigure(config)
+    }
+}
+
+impl fmt::Debug for Tracing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracing")
+            .field("sinks", &self.sinks())
+            .field("default_filter", &self.default_filter)
+            .field("color_choice", &self.color_choice)
+            .field("overrides", &self.overrides)
+            .finish()
     }
 }
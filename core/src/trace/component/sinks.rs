@@ -0,0 +1,323 @@
+//! Layer which writes events to sinks, each with their own filter.
+
+use super::super::{
+    Format,
+    config::{Config, SpanEvents},
+    file::RollingFile,
+    sink,
+};
+use crate::{
+    FrameworkError,
+    FrameworkErrorKind::ConfigError,
+    terminal::{
+        ColorChoice,
+        color::{self, Stream},
+    },
+};
+
+#[cfg(unix)]
+use super::super::{Journald, Syslog};
+use std::{
+    any::TypeId,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use tracing::{
+    Event, Metadata,
+    callsite::rebuild_interest_cache,
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    fmt::{
+        self, MakeWriter,
+        format::{DefaultFields, FmtSpan, Format as EventFormat, Full, JsonFields, Pretty},
+        time::FormatTime,
+    },
+    layer::Context,
+};
+
+/// Type-erased layer which formats and writes events.
+type OutputLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Sink which has been opened.
+pub(super) struct Sink {
+    /// Name of the sink
+    pub(super) name: String,
+
+    /// Does this sink use the default filter (as opposed to its own)?
+    pub(super) default_filter: bool,
+
+    /// Filter which determines which events are written to this sink
+    pub(super) filter: EnvFilter,
+
+    /// Layer which formats and writes events
+    layer: OutputLayer,
+
+    /// Type of the field formatter used by the layer (if it's a `fmt` layer)
+    fields: Option<TypeId>,
+
+    /// Log file being written to (if any)
+    pub(super) file: Option<RollingFile>,
+}
+
+impl Sink {
+    /// Open the sink with the given name and configuration, using the given
+    /// tracing configuration for defaults.
+    pub(super) fn open(
+        name: &str,
+        sink: &sink::Config,
+        config: &Config,
+        color_choice: ColorChoice,
+    ) -> Result<Self, FrameworkError> {
+        let filter = match &sink.level {
+            Some(level) => parse_filter(level)?,
+            None => parse_filter(&config.filter())?,
+        };
+
+        let format = sink.format.unwrap_or(config.format);
+        let mut file = None;
+        let mut fields = Some(field_formatter(format));
+
+        let layer = match &sink.output {
+            sink::Output::Stdout => {
+                let ansi = color::enabled(color_choice, Stream::Stdout);
+                output_layer(config, format, ansi, std::io::stdout)
+            }
+            sink::Output::Stderr => {
+                let ansi = color::enabled(color_choice, Stream::Stderr);
+                output_layer(config, format, ansi, std::io::stderr)
+            }
+            sink::Output::File(file_config) => {
+                let rolling_file = RollingFile::open(file_config.clone())?;
+                file = Some(rolling_file.clone());
+                output_layer(config, format, false, rolling_file)
+            }
+            #[cfg(unix)]
+            sink::Output::Syslog(syslog) => {
+                fields = None;
+                Box::new(Syslog::new(syslog.clone())?)
+            }
+            #[cfg(unix)]
+            sink::Output::Journald(journald) => {
+                fields = None;
+                Box::new(Journald::new(journald.clone())?)
+            }
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            default_filter: sink.level.is_none(),
+            filter,
+            layer,
+            fields,
+            file,
+        })
+    }
+}
+
+/// Layer which writes each event to the sinks whose filters enable it.
+///
+/// Clones of this layer share the same sinks, which can be replaced at
+/// runtime.
+#[derive(Clone, Default)]
+pub(super) struct Sinks(Arc<RwLock<Vec<Sink>>>);
+
+impl Sinks {
+    /// Acquire a read lock on the sinks, ignoring poisoning.
+    pub(super) fn read(&self) -> RwLockReadGuard<'_, Vec<Sink>> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Acquire a write lock on the sinks, ignoring poisoning.
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<Sink>> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replace all of the sinks.
+    pub(super) fn replace(&self, sinks: Vec<Sink>) {
+        *self.write() = sinks;
+        rebuild_interest_cache();
+    }
+
+    /// Replace the filters of the sinks matching the given predicate.
+    ///
+    /// Returns the number of sinks which were updated.
+    pub(super) fn reload_filters(
+        &self,
+        filter: &str,
+        predicate: impl Fn(&Sink) -> bool,
+    ) -> Result<usize, FrameworkError> {
+        // Validate the filter before updating any of the sinks
+        parse_filter(filter)?;
+        let mut updated = 0;
+
+        for sink in self.write().iter_mut().filter(|sink| predicate(sink)) {
+            sink.filter = parse_filter(filter)?;
+            updated += 1;
+        }
+
+        // Must be called after the write lock is released, as it calls
+        // `register_callsite` below
+        rebuild_interest_cache();
+        Ok(updated)
+    }
+}
+
+impl Layer<Registry> for Sinks {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        let sinks = self.read();
+        let mut always = true;
+        let mut never = true;
+
+        for sink in sinks.iter() {
+            let interest = Layer::<Registry>::register_callsite(&sink.filter, metadata);
+            always &= interest.is_always();
+            never &= interest.is_never();
+        }
+
+        if never {
+            Interest::never()
+        } else if always {
+            Interest::always()
+        } else {
+            Interest::sometimes()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, Registry>) -> bool {
+        self.read()
+            .iter()
+            .any(|sink| Layer::<Registry>::enabled(&sink.filter, metadata, ctx.clone()))
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            Layer::<Registry>::on_new_span(&sink.filter, attrs, id, ctx.clone());
+            sink.layer.on_new_span(attrs, id, ctx.clone());
+        }
+    }
+
+    fn on_record(&self, span: &Id, values: &Record<'_>, ctx: Context<'_, Registry>) {
+        // `fmt` layers with the same field formatter share the span's
+        // formatted fields, so only the first of them records new values
+        // (otherwise they'd be appended once per layer)
+        let mut recorded = vec![];
+
+        for sink in self.read().iter() {
+            Layer::<Registry>::on_record(&sink.filter, span, values, ctx.clone());
+
+            if let Some(fields) = sink.fields {
+                if recorded.contains(&fields) {
+                    continue;
+                }
+
+                recorded.push(fields);
+            }
+
+            sink.layer.on_record(span, values, ctx.clone());
+        }
+    }
+
+    fn on_follows_from(&self, span: &Id, follows: &Id, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            sink.layer.on_follows_from(span, follows, ctx.clone());
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            if Layer::<Registry>::enabled(&sink.filter, event.metadata(), ctx.clone()) {
+                sink.layer.on_event(event, ctx.clone());
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            Layer::<Registry>::on_enter(&sink.filter, id, ctx.clone());
+            sink.layer.on_enter(id, ctx.clone());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            Layer::<Registry>::on_exit(&sink.filter, id, ctx.clone());
+            sink.layer.on_exit(id, ctx.clone());
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            Layer::<Registry>::on_close(&sink.filter, id.clone(), ctx.clone());
+            sink.layer.on_close(id.clone(), ctx.clone());
+        }
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, Registry>) {
+        for sink in self.read().iter() {
+            sink.layer.on_id_change(old, new, ctx.clone());
+        }
+    }
+}
+
+/// Parse a filter in `RUST_LOG` syntax.
+fn parse_filter(filter: &str) -> Result<EnvFilter, FrameworkError> {
+    EnvFilter::try_new(filter)
+        .map_err(|e| format_err!(ConfigError, "invalid tracing filter {:?}: {}", filter, e).into())
+}
+
+/// Create a layer which writes events to the given writer.
+fn output_layer<W>(config: &Config, format: Format, ansi: bool, writer: W) -> OutputLayer
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
+        .with_ansi(ansi)
+        .with_writer(writer)
+        .with_span_events(match config.span_events {
+            SpanEvents::None => FmtSpan::NONE,
+            SpanEvents::New => FmtSpan::NEW,
+            SpanEvents::Close => FmtSpan::CLOSE,
+            SpanEvents::Active => FmtSpan::ACTIVE,
+            SpanEvents::Full => FmtSpan::FULL,
+        });
+
+    if config.timestamps {
+        format_layer(layer, format)
+    } else {
+        format_layer(layer.without_time(), format)
+    }
+}
+
+/// Type of the field formatter used by layers with the given format.
+fn field_formatter(format: Format) -> TypeId {
+    match format {
+        Format::Full | Format::Compact => TypeId::of::<DefaultFields>(),
+        Format::Pretty => TypeId::of::<Pretty>(),
+        Format::Json => TypeId::of::<JsonFields>(),
+    }
+}
+
+/// Apply the given format to a layer.
+fn format_layer<W, T>(
+    layer: fmt::Layer<Registry, DefaultFields, EventFormat<Full, T>, W>,
+    format: Format,
+) -> OutputLayer
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    T: FormatTime + Send + Sync + 'static,
+{
+    match format {
+        Format::Full => layer.boxed(),
+        Format::Compact => layer.compact().boxed(),
+        Format::Pretty => layer.pretty().boxed(),
+        Format::Json => layer
+            .json()
+            .with_ansi(false)
+            .with_thread_names(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    }
+}
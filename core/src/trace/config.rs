@@ -1,9 +1,65 @@
 //! Logging configuration
 
-/// Tracing configuration
+use super::{Format, file, sink};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Tracing configuration.
+///
+/// When the `serde` feature is enabled, this can be loaded from the
+/// `[tracing]` section of the application's configuration file, e.g.
+///
+/// ```toml
+/// [tracing]
+/// level = "info"
+/// format = "json"
+/// timestamps = true
+/// span_events = "close"
+///
+/// [tracing.directives]
+/// "myapp::db" = "debug"
+/// hyper = "warn"
+///
+/// [tracing.file]
+/// path = "/var/log/myapp.log"
+/// rotation = "daily"
+/// ```
+///
+/// Additional sinks, each with their own filter and format, can be
+/// configured in `[tracing.sinks]` (see the [`sink`] module).
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(default, deny_unknown_fields)
+)]
 pub struct Config {
-    pub(super) filter: String,
+    /// Default level (e.g. `info`), which can also be any filter accepted by
+    /// `RUST_LOG`, e.g. `info,hyper=warn`
+    pub level: String,
+
+    /// Levels for individual targets, which override the default level
+    pub directives: BTreeMap<String, String>,
+
+    /// Format of log output
+    pub format: Format,
+
+    /// Write log output to standard output (as the `stdout` sink)?
+    pub stdout: bool,
+
+    /// Write log output to a file (as the `file` sink)
+    pub file: Option<file::Config>,
+
+    /// Additional sinks, keyed by name
+    pub sinks: BTreeMap<String, sink::Config>,
+
+    /// Include timestamps in log output?
+    pub timestamps: bool,
+
+    /// Log events when spans are entered, exited, created and/or closed
+    pub span_events: SpanEvents,
 }
 
 impl Config {
@@ -11,18 +67,140 @@ impl Config {
     pub fn verbose() -> Self {
         "debug".to_owned().into()
     }
+
+    /// Set the format of log output.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Write log output to a file (in addition to standard output).
+    pub fn with_file(mut self, file: file::Config) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    /// Add a sink with the given name, replacing any existing sink with the
+    /// same name.
+    pub fn with_sink(mut self, name: impl Into<String>, sink: sink::Config) -> Self {
+        self.sinks.insert(name.into(), sink);
+        self
+    }
+
+    /// Get all of the sinks for this configuration, including the `stdout`
+    /// and `file` sinks (if enabled), keyed by name.
+    pub fn all_sinks(&self) -> BTreeMap<String, sink::Config> {
+        let mut sinks = BTreeMap::new();
+
+        if self.stdout {
+            sinks.insert(
+                sink::STDOUT.to_owned(),
+                sink::Config::new(sink::Output::Stdout),
+            );
+        }
+
+        if let Some(file) = &self.file {
+            sinks.insert(
+                sink::FILE.to_owned(),
+                sink::Config::new(sink::Output::File(file.clone())),
+            );
+        }
+
+        sinks.extend(self.sinks.clone());
+        sinks
+    }
+
+    /// Get the default filter for this configuration, in `RUST_LOG` syntax.
+    pub fn filter(&self) -> String {
+        let mut filter = self.level.clone();
+
+        for (target, level) in &self.directives {
+            if !filter.is_empty() {
+                filter.push(',');
+            }
+
+            filter.push_str(target);
+            filter.push('=');
+            filter.push_str(level);
+        }
+
+        filter
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
-        std::env::var("RUST_LOG")
-            .unwrap_or("info".to_owned())
-            .into()
+        Self {
+            level: std::env::var("RUST_LOG").unwrap_or("info".to_owned()),
+            directives: BTreeMap::new(),
+            format: Format::default(),
+            stdout: true,
+            file: None,
+            sinks: BTreeMap::new(),
+            timestamps: true,
+            span_events: SpanEvents::default(),
+        }
     }
 }
 
 impl From<String> for Config {
     fn from(filter: String) -> Self {
-        Self { filter }
+        Self {
+            level: filter,
+            ..Default::default()
+        }
     }
 }
+
+/// Tracing options given on the command line, which take precedence over
+/// the `[tracing]` section of the application's configuration file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Overrides {
+    /// Default level (e.g. from `-v` or `-q`), replacing the configured one
+    pub level: Option<String>,
+
+    /// Format of log output (e.g. from `--log-format`)
+    pub format: Option<Format>,
+}
+
+impl Overrides {
+    /// Apply these overrides to the given configuration.
+    ///
+    /// Levels configured for individual targets are kept.
+    pub fn apply(&self, mut config: Config) -> Config {
+        if let Some(level) = &self.level {
+            config.level = level.clone();
+        }
+
+        if let Some(format) = self.format {
+            config.format = format;
+        }
+
+        config
+    }
+}
+
+/// Span lifecycle events to log.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SpanEvents {
+    /// Don't log span events
+    #[default]
+    None,
+
+    /// Log when spans are created
+    New,
+
+    /// Log when spans are closed, including how long they were open
+    Close,
+
+    /// Log when spans are entered and exited
+    Active,
+
+    /// Log all span events
+    Full,
+}
@@ -0,0 +1,100 @@
+//! Span and event fields recorded by structured sinks.
+
+use std::fmt;
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+/// Name of the field containing an event's message.
+const MESSAGE: &str = "message";
+
+/// Fields of a span or event, formatted as strings.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Fields(pub(crate) Vec<(&'static str, String)>);
+
+impl Fields {
+    /// Record the fields of the given event, returning its message (if any)
+    /// along with the remaining fields.
+    pub(crate) fn from_event(event: &Event<'_>) -> (String, Self) {
+        let mut fields = Self::default();
+        event.record(&mut fields);
+
+        let message = match fields.0.iter().position(|(name, _)| *name == MESSAGE) {
+            Some(index) => fields.0.remove(index).1,
+            None => String::new(),
+        };
+
+        (message, fields)
+    }
+
+    /// Record the fields of the spans the given event occurred in, from the
+    /// outermost span inwards.
+    pub(crate) fn from_scope<S>(event: &Event<'_>, ctx: &Context<'_, S>) -> Self
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut fields = Self::default();
+
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(span_fields) = span.extensions().get::<Fields>() {
+                    for (name, value) in &span_fields.0 {
+                        fields.set(name, value.clone());
+                    }
+                }
+            }
+        }
+
+        fields
+    }
+
+    /// Set the value of a field, replacing any existing value.
+    fn set(&mut self, name: &'static str, value: String) {
+        match self.0.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((name, value)),
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.set(field.name(), format!("{:?}", value));
+    }
+}
+
+/// Record the fields of a new span in its extensions.
+///
+/// Multiple layers can record the same span, as recording is idempotent.
+pub(crate) fn record_new_span<S>(attrs: &Attributes<'_>, id: &Id, ctx: &Context<'_, S>)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let Some(span) = ctx.span(id) else { return };
+    let mut extensions = span.extensions_mut();
+
+    if extensions.get_mut::<Fields>().is_none() {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        extensions.insert(fields);
+    }
+}
+
+/// Record updated values of a span's fields in its extensions.
+pub(crate) fn record_span<S>(id: &Id, values: &Record<'_>, ctx: &Context<'_, S>)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let Some(span) = ctx.span(id) else { return };
+
+    if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+        values.record(fields);
+    }
+}
@@ -0,0 +1,359 @@
+//! Log file output with rotation.
+//!
+//! The [`RollingFile`] writer appends log output to a file, rotating it once
+//! it reaches a maximum size or the (UTC) day changes. Rotated files are
+//! numbered, with `app.log.1` being the most recent, and only the configured
+//! number of them are retained. Rotated files can optionally be compressed
+//! with gzip, e.g. `app.log.1.gz`.
+//!
+//! Output is buffered, and is flushed when:
+//!
+//! - a warning or error is logged
+//! - more than a second has passed since the last flush
+//! - the file is rotated
+//! - the [`Tracing`] component is shut down
+//!
+//! [`Tracing`]: super::Tracing
+
+use crate::{FrameworkError, FrameworkErrorKind::IoError, fs};
+use flate2::{Compression, write::GzEncoder};
+use std::{
+    ffi::OsString,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{Level, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default number of rotated log files to retain.
+pub const DEFAULT_RETENTION: usize = 7;
+
+/// Maximum amount of time buffered output is held before being flushed
+/// (checked whenever an event is logged).
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Seconds per day.
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Log file configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Config {
+    /// Path to the log file, e.g. `/var/lib/myapp/myapp.log`.
+    ///
+    /// Its parent directory is created if it doesn't already exist.
+    pub path: PathBuf,
+
+    /// When to rotate the log file
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation: Rotation,
+
+    /// Number of rotated log files to retain
+    #[cfg_attr(feature = "serde", serde(default = "default_retention"))]
+    pub retention: usize,
+
+    /// Compress rotated log files with gzip?
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compress: bool,
+}
+
+impl Config {
+    /// Create a new log file configuration for the given path, which by
+    /// default is never rotated.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            rotation: Rotation::default(),
+            retention: DEFAULT_RETENTION,
+            compress: false,
+        }
+    }
+}
+
+/// When to rotate a log file.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Rotation {
+    /// Never rotate the log file
+    #[default]
+    Never,
+
+    /// Rotate the log file before it would exceed the given size in bytes
+    Size(u64),
+
+    /// Rotate the log file when the (UTC) day changes
+    Daily,
+}
+
+/// Log file writer which rotates the file according to its [`Config`].
+///
+/// Clones of a `RollingFile` write to the same file.
+#[derive(Clone, Debug)]
+pub struct RollingFile {
+    /// Shared state of the writer
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl RollingFile {
+    /// Open the log file with the given configuration, creating it (along
+    /// with its parent directory) if it doesn't already exist.
+    pub fn open(config: Config) -> Result<Self, FrameworkError> {
+        if let Some(parent) = config.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = open_append(&config.path)?;
+        let metadata = file.get_ref().metadata()?;
+
+        // Attribute an existing log file to the day it was last modified so
+        // daily rotation accounts for output from previous runs
+        let day = match metadata.modified() {
+            Ok(modified) if metadata.len() > 0 => day(modified),
+            _ => day(SystemTime::now()),
+        };
+
+        let inner = Inner {
+            size: metadata.len(),
+            config,
+            file: Some(file),
+            day,
+            last_flush: Instant::now(),
+        };
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(inner)),
+        })
+    }
+
+    /// Get the path to the (current) log file.
+    pub fn path(&self) -> PathBuf {
+        self.lock().config.path.clone()
+    }
+
+    /// Flush buffered output to the log file.
+    pub fn flush(&self) -> Result<(), FrameworkError> {
+        self.lock()
+            .flush()
+            .map_err(|e| format_err!(IoError, "error flushing log file: {}", e).into())
+    }
+
+    /// Rotate the log file immediately.
+    pub fn rotate(&self) -> Result<(), FrameworkError> {
+        self.lock()
+            .rotate()
+            .map_err(|e| format_err!(IoError, "error rotating log file: {}", e).into())
+    }
+
+    /// Lock the shared state of this writer, ignoring poisoning.
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFile {
+    type Writer = Writer<'a>;
+
+    fn make_writer(&'a self) -> Writer<'a> {
+        Writer {
+            file: self,
+            flush: false,
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Writer<'a> {
+        Writer {
+            file: self,
+            flush: *meta.level() <= Level::WARN,
+        }
+    }
+}
+
+/// Writer for an individual event written to a [`RollingFile`].
+#[derive(Debug)]
+pub struct Writer<'a> {
+    /// Log file being written to
+    file: &'a RollingFile,
+
+    /// Flush output once the event has been written?
+    flush: bool,
+}
+
+impl Write for Writer<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.lock().flush()
+    }
+}
+
+impl Drop for Writer<'_> {
+    fn drop(&mut self) {
+        let mut inner = self.file.lock();
+
+        if self.flush || inner.last_flush.elapsed() >= FLUSH_INTERVAL {
+            let _ = inner.flush();
+        }
+    }
+}
+
+/// Shared state of a [`RollingFile`].
+#[derive(Debug)]
+struct Inner {
+    /// Log file configuration
+    config: Config,
+
+    /// Currently open log file (closed while it's being rotated)
+    file: Option<BufWriter<fs::File>>,
+
+    /// Size of the current log file in bytes
+    size: u64,
+
+    /// Day (since the Unix epoch) the current log file was opened
+    day: u64,
+
+    /// Time output was last flushed
+    last_flush: Instant,
+}
+
+impl Inner {
+    /// Write to the log file, rotating it first if necessary.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let rotate = match self.config.rotation {
+            Rotation::Never => false,
+            Rotation::Size(max) => self.size > 0 && self.size + buf.len() as u64 > max,
+            Rotation::Daily => day(SystemTime::now()) != self.day,
+        };
+
+        if rotate {
+            self.rotate()?;
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(open_append(&self.config.path)?),
+        };
+
+        file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    /// Flush buffered output to the log file.
+    fn flush(&mut self) -> io::Result<()> {
+        self.last_flush = Instant::now();
+
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Rotate the log file, shifting (and pruning) the previously rotated
+    /// files and opening a new, empty log file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.flush()?;
+
+        // Close the log file prior to renaming it (required on Windows)
+        self.file = None;
+
+        let path = &self.config.path;
+
+        if self.config.retention == 0 {
+            remove_if_exists(path)?;
+        } else {
+            remove_if_exists(&self.rotated_path(self.config.retention))?;
+
+            for n in (1..self.config.retention).rev() {
+                rename_if_exists(&self.rotated_path(n), &self.rotated_path(n + 1))?;
+            }
+
+            if self.config.compress {
+                compress(path, &self.rotated_path(1))?;
+                fs::remove_file(path)?;
+            } else {
+                fs::rename(path, self.rotated_path(1))?;
+            }
+        }
+
+        self.file = Some(open_append(path)?);
+        self.size = 0;
+        self.day = day(SystemTime::now());
+        Ok(())
+    }
+
+    /// Get the path of the `n`th most recently rotated log file.
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = OsString::from(&self.config.path);
+        path.push(format!(".{}", n));
+
+        if self.config.compress {
+            path.push(".gz");
+        }
+
+        path.into()
+    }
+}
+
+/// Open the given file for appending, creating it if it doesn't exist.
+fn open_append(path: &Path) -> io::Result<BufWriter<fs::File>> {
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map(BufWriter::new)
+}
+
+/// Compress the given file with gzip.
+fn compress(src: &Path, dst: &Path) -> io::Result<()> {
+    let mut input = fs::File::open(src)?;
+    let mut encoder = GzEncoder::new(fs::File::create(dst)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()
+}
+
+/// Remove the given file, ignoring it if it doesn't exist.
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Rename the given file, ignoring it if it doesn't exist.
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Get the default number of rotated log files to retain.
+#[cfg(feature = "serde")]
+fn default_retention() -> usize {
+    DEFAULT_RETENTION
+}
+
+/// Get the day (since the Unix epoch) of the given time.
+fn day(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY
+}
@@ -0,0 +1,41 @@
+//! Log output formats
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Formats in which log output can be written.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "options", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Format {
+    /// Human-readable single-line format, including span context
+    #[default]
+    Full,
+
+    /// Abbreviated single-line format
+    Compact,
+
+    /// Verbose multi-line format, intended for local development
+    Pretty,
+
+    /// Newline-delimited JSON objects including span fields, thread names,
+    /// and RFC 3339 timestamps, intended for log aggregation
+    Json,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Full => "full",
+            Format::Compact => "compact",
+            Format::Pretty => "pretty",
+            Format::Json => "json",
+        })
+    }
+}
@@ -0,0 +1,193 @@
+//! Journald sink, which sends events to the systemd journal using its native
+//! protocol.
+//!
+//! ```toml
+//! [tracing.sinks.journal]
+//! output = { journald = {} }
+//! ```
+//!
+//! Each event is sent as a journal entry with the following fields, along
+//! with the fields of the event and the spans it occurred in (with their
+//! names converted to uppercase, e.g. `request_id` becomes `REQUEST_ID`):
+//!
+//! - `MESSAGE`: message of the event
+//! - `PRIORITY`: syslog severity of the event's level (see [`severity`])
+//! - `SYSLOG_IDENTIFIER`: name of the application
+//! - `TARGET`: target of the event
+//! - `CODE_FILE`, `CODE_LINE`: source location of the event (if known)
+//!
+//! Entries which exceed the maximum datagram size of the journal socket are
+//! dropped.
+//!
+//! [`severity`]: super::syslog::severity
+
+use super::{
+    fields::{self, Fields},
+    syslog::severity,
+};
+use crate::{FrameworkError, FrameworkErrorKind::IoError};
+use std::{env, os::unix::net::UnixDatagram, path::PathBuf};
+use tracing::{
+    Event, Subscriber,
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default path of the journal socket.
+pub const DEFAULT_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Journald sink configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct Config {
+    /// Path to the journal socket
+    pub socket: PathBuf,
+
+    /// Value of the `SYSLOG_IDENTIFIER` field (defaults to the name of the
+    /// current executable)
+    pub syslog_identifier: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            socket: DEFAULT_SOCKET.into(),
+            syslog_identifier: None,
+        }
+    }
+}
+
+/// Layer which sends events to the systemd journal.
+#[derive(Debug)]
+pub struct Journald {
+    /// Socket connected to the journal
+    socket: UnixDatagram,
+
+    /// Value of the `SYSLOG_IDENTIFIER` field
+    syslog_identifier: String,
+}
+
+impl Journald {
+    /// Connect to the journal with the given configuration.
+    pub fn new(config: Config) -> Result<Self, FrameworkError> {
+        let socket = UnixDatagram::unbound()
+            .and_then(|socket| socket.connect(&config.socket).map(|_| socket))
+            .map_err(|e| {
+                format_err!(
+                    IoError,
+                    "error connecting to journal socket {}: {}",
+                    config.socket.display(),
+                    e
+                )
+            })?;
+
+        let syslog_identifier = config.syslog_identifier.unwrap_or_else(|| {
+            env::current_exe()
+                .ok()
+                .and_then(|exe| Some(exe.file_name()?.to_string_lossy().into_owned()))
+                .unwrap_or_default()
+        });
+
+        Ok(Self {
+            socket,
+            syslog_identifier,
+        })
+    }
+
+    /// Encode an event as a journal entry.
+    fn encode<S>(&self, event: &Event<'_>, ctx: &Context<'_, S>) -> Vec<u8>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let metadata = event.metadata();
+        let (message, event_fields) = Fields::from_event(event);
+        let mut entry = vec![];
+
+        put_field(&mut entry, "MESSAGE", &message);
+        put_field(
+            &mut entry,
+            "PRIORITY",
+            &severity(metadata.level()).to_string(),
+        );
+        put_field(&mut entry, "SYSLOG_IDENTIFIER", &self.syslog_identifier);
+        put_field(&mut entry, "TARGET", metadata.target());
+
+        if let Some(file) = metadata.file() {
+            put_field(&mut entry, "CODE_FILE", file);
+        }
+
+        if let Some(line) = metadata.line() {
+            put_field(&mut entry, "CODE_LINE", &line.to_string());
+        }
+
+        let span_fields = Fields::from_scope(event, ctx);
+
+        for (name, value) in span_fields.0.iter().chain(&event_fields.0) {
+            put_field(&mut entry, &field_name(name), value);
+        }
+
+        entry
+    }
+}
+
+impl<S> Layer<S> for Journald
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        fields::record_new_span(attrs, id, &ctx);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        fields::record_span(id, values, &ctx);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // Errors can't be logged, so entries which can't be sent are dropped
+        let _ = self.socket.send(&self.encode(event, &ctx));
+    }
+}
+
+/// Convert the name of a tracing field to a journal field name, which must
+/// consist of uppercase letters, digits, and underscores, and can't begin
+/// with an underscore or digit.
+fn field_name(name: &str) -> String {
+    let mut field = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => c.to_ascii_uppercase(),
+            'A'..='Z' | '0'..='9' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+
+    if !field.starts_with(|c: char| c.is_ascii_uppercase()) {
+        field.insert_str(0, "F_");
+    }
+
+    field
+}
+
+/// Append a field to a journal entry.
+///
+/// Values containing newlines are encoded with an explicit length.
+fn put_field(entry: &mut Vec<u8>, name: &str, value: &str) {
+    entry.extend_from_slice(name.as_bytes());
+
+    if value.contains('\n') {
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+
+    entry.extend_from_slice(value.as_bytes());
+    entry.push(b'\n');
+}
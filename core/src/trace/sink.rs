@@ -0,0 +1,109 @@
+//! Tracing sinks: destinations for log output.
+//!
+//! Each sink has its own filter and format, e.g. to write debug-level output
+//! as JSON to a file while only writing warnings to the terminal:
+//!
+//! ```toml
+//! [tracing]
+//! stdout = false
+//!
+//! [tracing.sinks.terminal]
+//! output = "stderr"
+//! level = "warn"
+//!
+//! [tracing.sinks.debug]
+//! output = { file = { path = "/var/log/myapp/debug.json" } }
+//! level = "debug"
+//! format = "json"
+//! ```
+//!
+//! The filter of each sink can be reloaded by name using
+//! [`Tracing::reload_sink_filter`].
+//!
+//! [`Tracing::reload_sink_filter`]: super::Tracing::reload_sink_filter
+
+use super::{Format, file};
+
+#[cfg(unix)]
+use super::{journald, syslog};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Name of the sink which writes to standard output when
+/// [`Config::stdout`](super::Config::stdout) is enabled.
+pub const STDOUT: &str = "stdout";
+
+/// Name of the sink which writes to [`Config::file`](super::Config::file)
+/// when it's configured.
+pub const FILE: &str = "file";
+
+/// Sink configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Config {
+    /// Where log output is written
+    pub output: Output,
+
+    /// Filter for this sink (in `RUST_LOG` syntax), or the default filter
+    /// if unspecified
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub level: Option<String>,
+
+    /// Format of log output, or the default format if unspecified
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: Option<Format>,
+}
+
+impl Config {
+    /// Create a sink configuration which uses the default filter and format.
+    pub fn new(output: Output) -> Self {
+        Self {
+            output,
+            level: None,
+            format: None,
+        }
+    }
+
+    /// Set the filter for this sink.
+    pub fn with_level(mut self, level: impl Into<String>) -> Self {
+        self.level = Some(level.into());
+        self
+    }
+
+    /// Set the format of log output for this sink.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+/// Where a sink writes log output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Output {
+    /// Standard output
+    Stdout,
+
+    /// Standard error
+    Stderr,
+
+    /// Log file
+    File(file::Config),
+
+    /// Syslog (using the RFC 5424 protocol), which ignores the sink's format
+    #[cfg(unix)]
+    Syslog(syslog::Config),
+
+    /// Systemd journal, which ignores the sink's format
+    #[cfg(unix)]
+    Journald(journald::Config),
+}
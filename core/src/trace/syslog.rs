@@ -0,0 +1,380 @@
+//! Syslog sink, which sends events using the RFC 5424 syslog protocol.
+//!
+//! Messages are sent as datagrams to a local Unix socket (by default
+//! `/dev/log`) or to a remote host over UDP:
+//!
+//! ```toml
+//! [tracing.sinks.syslog]
+//! output = { syslog = { transport = { udp = "10.0.0.1:514" }, facility = "daemon" } }
+//! ```
+//!
+//! Levels are mapped to severities (see [`severity`]), and the fields of the
+//! event and the spans it occurred in are sent as structured data.
+
+use super::fields::{self, Fields};
+use crate::{FrameworkError, FrameworkErrorKind::IoError, fs};
+use std::{
+    env,
+    fmt::Write as _,
+    net::{SocketAddr, UdpSocket},
+    os::unix::net::UnixDatagram,
+    path::PathBuf,
+    process,
+};
+use tracing::{
+    Event, Level, Subscriber,
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{
+    Layer,
+    fmt::{format::Writer, time::FormatTime},
+    layer::Context,
+    registry::LookupSpan,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default path of the local syslog socket.
+pub const DEFAULT_SOCKET: &str = "/dev/log";
+
+/// Default ID of the structured data element containing event fields.
+///
+/// Uses the private enterprise number reserved for documentation (32473).
+pub const DEFAULT_STRUCTURED_DATA_ID: &str = "tracing@32473";
+
+/// Maximum length of the `APP-NAME` header field.
+const MAX_APP_NAME_LEN: usize = 48;
+
+/// Maximum length of the `HOSTNAME` header field.
+const MAX_HOSTNAME_LEN: usize = 255;
+
+/// Maximum length of structured data parameter names.
+const MAX_PARAM_NAME_LEN: usize = 32;
+
+/// Syslog sink configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct Config {
+    /// Where messages are sent
+    pub transport: Transport,
+
+    /// Facility messages are logged under
+    pub facility: Facility,
+
+    /// Application name (defaults to the name of the current executable)
+    pub app_name: Option<String>,
+
+    /// Hostname (defaults to the system's hostname)
+    pub hostname: Option<String>,
+
+    /// ID of the structured data element containing event fields
+    pub structured_data_id: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            transport: Transport::default(),
+            facility: Facility::default(),
+            app_name: None,
+            hostname: None,
+            structured_data_id: DEFAULT_STRUCTURED_DATA_ID.to_owned(),
+        }
+    }
+}
+
+/// Transport over which syslog messages are sent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Transport {
+    /// Local Unix datagram socket at the given path
+    Unix(PathBuf),
+
+    /// UDP to the given address
+    Udp(SocketAddr),
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Transport::Unix(DEFAULT_SOCKET.into())
+    }
+}
+
+/// Syslog facilities.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Facility {
+    /// Kernel messages
+    Kern = 0,
+
+    /// User-level messages
+    #[default]
+    User = 1,
+
+    /// Mail system
+    Mail = 2,
+
+    /// System daemons
+    Daemon = 3,
+
+    /// Security/authorization messages
+    Auth = 4,
+
+    /// Messages generated internally by syslogd
+    Syslog = 5,
+
+    /// Line printer subsystem
+    Lpr = 6,
+
+    /// Network news subsystem
+    News = 7,
+
+    /// UUCP subsystem
+    Uucp = 8,
+
+    /// Clock daemon
+    Cron = 9,
+
+    /// Private security/authorization messages
+    Authpriv = 10,
+
+    /// FTP daemon
+    Ftp = 11,
+
+    /// Local use 0
+    Local0 = 16,
+
+    /// Local use 1
+    Local1 = 17,
+
+    /// Local use 2
+    Local2 = 18,
+
+    /// Local use 3
+    Local3 = 19,
+
+    /// Local use 4
+    Local4 = 20,
+
+    /// Local use 5
+    Local5 = 21,
+
+    /// Local use 6
+    Local6 = 22,
+
+    /// Local use 7
+    Local7 = 23,
+}
+
+/// Get the syslog severity corresponding to the given level:
+///
+/// | Level   | Severity          |
+/// |---------|-------------------|
+/// | `ERROR` | 3 (error)         |
+/// | `WARN`  | 4 (warning)       |
+/// | `INFO`  | 6 (informational) |
+/// | `DEBUG` | 7 (debug)         |
+/// | `TRACE` | 7 (debug)         |
+pub fn severity(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+/// Layer which sends events to syslog.
+#[derive(Debug)]
+pub struct Syslog {
+    /// Socket messages are sent over
+    socket: Socket,
+
+    /// Facility messages are logged under
+    facility: Facility,
+
+    /// `APP-NAME` header field
+    app_name: String,
+
+    /// `HOSTNAME` header field
+    hostname: String,
+
+    /// ID of the structured data element containing event fields
+    structured_data_id: String,
+}
+
+impl Syslog {
+    /// Connect to syslog with the given configuration.
+    pub fn new(config: Config) -> Result<Self, FrameworkError> {
+        let socket = match &config.transport {
+            Transport::Unix(path) => UnixDatagram::unbound()
+                .and_then(|socket| socket.connect(path).map(|_| socket))
+                .map(Socket::Unix),
+            Transport::Udp(addr) => {
+                let local_addr = if addr.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                UdpSocket::bind(local_addr)
+                    .and_then(|socket| socket.connect(addr).map(|_| socket))
+                    .map(Socket::Udp)
+            }
+        }
+        .map_err(|e| format_err!(IoError, "error connecting to syslog: {}", e))?;
+
+        let app_name = config.app_name.unwrap_or_else(|| {
+            env::current_exe()
+                .ok()
+                .and_then(|exe| Some(exe.file_name()?.to_string_lossy().into_owned()))
+                .unwrap_or_default()
+        });
+
+        let hostname = config.hostname.unwrap_or_else(|| {
+            fs::read_to_string("/proc/sys/kernel/hostname")
+                .or_else(|_| fs::read_to_string("/etc/hostname"))
+                .map(|hostname| hostname.trim().to_owned())
+                .unwrap_or_default()
+        });
+
+        Ok(Self {
+            socket,
+            facility: config.facility,
+            app_name: header_field(&app_name, MAX_APP_NAME_LEN),
+            hostname: header_field(&hostname, MAX_HOSTNAME_LEN),
+            structured_data_id: config.structured_data_id,
+        })
+    }
+
+    /// Format an event as an RFC 5424 message.
+    fn format<S>(&self, event: &Event<'_>, ctx: &Context<'_, S>) -> String
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let metadata = event.metadata();
+        let priority = self.facility as u8 * 8 + severity(metadata.level());
+
+        let mut timestamp = String::new();
+        let _ =
+            tracing_subscriber::fmt::time::SystemTime.format_time(&mut Writer::new(&mut timestamp));
+
+        let (message, event_fields) = Fields::from_event(event);
+        let mut fields = Fields::from_scope(event, ctx);
+        fields.0.extend(event_fields.0);
+
+        let mut msg = format!(
+            "<{}>1 {} {} {} {} - [{} target=\"{}\"",
+            priority,
+            timestamp,
+            self.hostname,
+            self.app_name,
+            process::id(),
+            self.structured_data_id,
+            escape_param_value(metadata.target()),
+        );
+
+        for (name, value) in &fields.0 {
+            let _ = write!(
+                msg,
+                " {}=\"{}\"",
+                param_name(name),
+                escape_param_value(value)
+            );
+        }
+
+        // Messages are UTF-8, which RFC 5424 requires to be indicated by a BOM
+        let _ = write!(msg, "] \u{feff}{}", message);
+        msg
+    }
+}
+
+impl<S> Layer<S> for Syslog
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        fields::record_new_span(attrs, id, &ctx);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        fields::record_span(id, values, &ctx);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let msg = self.format(event, &ctx);
+
+        // Errors can't be logged, so messages which can't be sent are dropped
+        let _ = match &self.socket {
+            Socket::Unix(socket) => socket.send(msg.as_bytes()),
+            Socket::Udp(socket) => socket.send(msg.as_bytes()),
+        };
+    }
+}
+
+/// Socket syslog messages are sent over.
+#[derive(Debug)]
+enum Socket {
+    /// Local Unix datagram socket
+    Unix(UnixDatagram),
+
+    /// UDP socket
+    Udp(UdpSocket),
+}
+
+/// Sanitize a header field, which must consist of printable ASCII characters
+/// and can't exceed the given length, using `-` if it's empty.
+fn header_field(value: &str, max_len: usize) -> String {
+    let field = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_len)
+        .collect::<String>();
+
+    if field.is_empty() {
+        "-".to_owned()
+    } else {
+        field
+    }
+}
+
+/// Sanitize a structured data parameter name, which can't contain `=`,
+/// spaces, `]`, or `"`.
+fn param_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '=' | ']' | '"' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .take(MAX_PARAM_NAME_LEN)
+        .collect()
+}
+
+/// Escape a structured data parameter value, in which `"`, `\`, and `]`
+/// must be escaped with a backslash.
+fn escape_param_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
@@ -3,7 +3,27 @@
 mod example_app;
 
 use self::example_app::{ExampleApp, ExampleConfig};
-use abscissa_core::{Component, FrameworkError, FrameworkErrorKind::ComponentError, component};
+use abscissa_core::{
+    Component, FrameworkError,
+    FrameworkErrorKind::{ComponentError, ConfigError},
+    Injectable, Shutdown, Version,
+    component::{
+        self, ConfigSection, Health,
+        timing::{self, Phase},
+    },
+    config::Sections,
+    event::{self, Subscription},
+    fs,
+    path::AbsPathBuf,
+};
+use serde::Deserialize;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 /// ID for `FoobarComponent` (example component #1)
 const FOOBAR_COMPONENT_ID: component::Id = component::Id::new("component::FoobarComponent");
@@ -14,6 +34,9 @@ const BAZ_COMPONENT_ID: component::Id = component::Id::new("component::BazCompon
 /// ID for `QuuxComponent` (example component #3)
 const QUUX_COMPONENT_ID: component::Id = component::Id::new("component::QuuxComponent");
 
+/// ID for `DegradedComponent` (example component #4)
+const DEGRADED_COMPONENT_ID: component::Id = component::Id::new("component::DegradedComponent");
+
 /// Example component #1
 #[derive(Component, Debug, Default)]
 pub struct FoobarComponent {
@@ -59,6 +82,121 @@ impl QuuxComponent {
     }
 }
 
+/// Example component #4: reports itself as degraded
+#[derive(Debug, Default, Injectable)]
+pub struct DegradedComponent {
+    /// Number of times the component has been shut down
+    pub shutdowns: Arc<AtomicUsize>,
+}
+
+impl Component<ExampleApp> for DegradedComponent {
+    fn before_shutdown(&self, _kind: Shutdown) -> Result<(), FrameworkError> {
+        self.shutdowns.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn health(&self) -> Health {
+        Health::Degraded("running on fumes".to_owned())
+    }
+}
+
+/// Configuration section for `DatabaseComponent`
+#[derive(Clone, Debug, Deserialize)]
+pub struct DatabaseConfig {
+    /// Database URL
+    pub url: String,
+}
+
+/// Example component #5: configured by its own `[database]` section
+#[derive(Component, Debug, Default)]
+#[component(config = "database")]
+pub struct DatabaseComponent {
+    /// Configuration loaded from the `[database]` section
+    pub config: Option<DatabaseConfig>,
+}
+
+impl ConfigSection for DatabaseComponent {
+    type Config = DatabaseConfig;
+
+    fn after_config_section(&mut self, config: DatabaseConfig) -> Result<(), FrameworkError> {
+        self.config = Some(config);
+        Ok(())
+    }
+}
+
+/// Event published when the cache should be flushed
+#[derive(Clone, Debug)]
+pub struct FlushCache;
+
+/// Example component #6: subscribes to `FlushCache` events
+#[derive(Debug, Default, Injectable)]
+pub struct CacheComponent {
+    /// Number of times the cache has been flushed
+    pub flushes: Arc<AtomicUsize>,
+
+    /// Fail the `after_config` callback?
+    pub fail_config: bool,
+}
+
+impl Component<ExampleApp> for CacheComponent {
+    fn after_config(&mut self, _config: &ExampleConfig) -> Result<(), FrameworkError> {
+        if self.fail_config {
+            Err(ConfigError.context("invalid cache configuration").into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn subscribe(&mut self, events: &event::Bus) -> Result<Vec<Subscription>, FrameworkError> {
+        let flushes = Arc::clone(&self.flushes);
+
+        Ok(vec![events.subscribe(move |_: &FlushCache| {
+            flushes.fetch_add(1, Ordering::SeqCst);
+        })])
+    }
+}
+
+/// Example component #7: declares a config section without loading it
+#[derive(Debug, Default)]
+pub struct UnloadedComponent {}
+
+impl Injectable<ExampleApp> for UnloadedComponent {
+    fn id(&self) -> component::Id {
+        component::Id::new("component::UnloadedComponent")
+    }
+
+    fn version(&self) -> Version {
+        Version::new(0, 0, 0)
+    }
+
+    fn config_section(&self) -> Option<&'static str> {
+        Some("unloaded")
+    }
+}
+
+impl Component<ExampleApp> for UnloadedComponent {}
+
+/// Example component #8: claims the ID of `DegradedComponent`
+#[derive(Debug, Default)]
+pub struct ImpostorComponent {}
+
+impl Injectable<ExampleApp> for ImpostorComponent {
+    fn id(&self) -> component::Id {
+        DEGRADED_COMPONENT_ID
+    }
+
+    fn version(&self) -> Version {
+        Version::new(0, 0, 0)
+    }
+}
+
+impl Component<ExampleApp> for ImpostorComponent {}
+
+/// Create a new registry with its own event bus
+fn new_registry() -> component::Registry<ExampleApp> {
+    component::Registry::new(Arc::default())
+}
+
 fn init_components() -> Vec<Box<dyn Component<ExampleApp>>> {
     let mut foobar = FoobarComponent::default();
     foobar.set_state("original foobar state");
@@ -79,7 +217,7 @@ fn init_components() -> Vec<Box<dyn Component<ExampleApp>>> {
 
 #[test]
 fn component_registration() {
-    let mut registry = component::Registry::default();
+    let mut registry = new_registry();
     assert!(registry.is_empty());
 
     let components = init_components();
@@ -103,7 +241,7 @@ fn duplicate_component_registration() {
     let foobar2 = Box::<FoobarComponent>::default();
     let components: Vec<Box<dyn Component<ExampleApp>>> = vec![foobar1, foobar2];
 
-    let mut registry = component::Registry::default();
+    let mut registry = new_registry();
     assert!(registry.is_empty());
 
     let err = registry.register(components).err().unwrap();
@@ -116,7 +254,7 @@ fn duplicate_component_registration() {
 
 #[test]
 fn get_downcast_ref() {
-    let mut registry = component::Registry::default();
+    let mut registry = new_registry();
     let component = Box::<FoobarComponent>::default() as Box<dyn Component<ExampleApp>>;
     registry.register(vec![component]).unwrap();
 
@@ -133,7 +271,7 @@ fn get_downcast_ref() {
 
 #[test]
 fn dependency_injection() {
-    let mut registry = component::Registry::default();
+    let mut registry = new_registry();
     let mut components = init_components();
 
     // Start component up in reverse order to make sure sorting works
@@ -151,3 +289,512 @@ fn dependency_injection() {
         "original foobar state"
     );
 }
+
+#[test]
+fn health_report() {
+    let mut registry = new_registry();
+    registry.register(init_components()).unwrap();
+
+    let report = registry.health_report();
+    assert!(report.health().is_healthy());
+    assert_eq!(report.iter().count(), 3);
+
+    let mut registry = new_registry();
+    let mut components = init_components();
+    components.push(Box::<DegradedComponent>::default());
+    registry.register(components).unwrap();
+
+    let report = registry.health_report();
+    assert_eq!(report.health().as_str(), "degraded");
+
+    let degraded = report
+        .iter()
+        .find(|entry| entry.id.as_ref() == "component::DegradedComponent")
+        .unwrap();
+    assert_eq!(degraded.health.details(), Some("running on fumes"));
+
+    let json = report.to_json();
+    assert!(json.contains(r#""status": "degraded""#));
+    assert!(json.contains(r#""details": "running on fumes""#));
+}
+
+#[test]
+fn config_sections() {
+    let mut registry = new_registry();
+    let component = Box::<DatabaseComponent>::default() as Box<dyn Component<ExampleApp>>;
+    assert_eq!(component.config_section(), Some("database"));
+    registry.register(vec![component]).unwrap();
+
+    let sections = Sections::load_toml("[database]\nurl = \"postgres://localhost\"").unwrap();
+    registry.set_config_sections(sections).unwrap();
+    registry.after_config(&ExampleConfig::default()).unwrap();
+
+    let database = registry.get_downcast_ref::<DatabaseComponent>().unwrap();
+    assert_eq!(
+        database.config.as_ref().unwrap().url,
+        "postgres://localhost"
+    );
+}
+
+#[test]
+fn missing_config_section() {
+    let mut registry = new_registry();
+    let component = Box::<DatabaseComponent>::default() as Box<dyn Component<ExampleApp>>;
+    registry.register(vec![component]).unwrap();
+    registry.after_config(&ExampleConfig::default()).unwrap();
+
+    let database = registry.get_downcast_ref::<DatabaseComponent>().unwrap();
+    assert!(database.config.is_none());
+}
+
+#[test]
+fn invalid_config_section() {
+    let mut registry = new_registry();
+    let component = Box::<DatabaseComponent>::default() as Box<dyn Component<ExampleApp>>;
+    registry.register(vec![component]).unwrap();
+
+    let sections = Sections::load_toml("[database]\nurl = 42").unwrap();
+    registry.set_config_sections(sections).unwrap();
+
+    let err = registry
+        .after_config(&ExampleConfig::default())
+        .unwrap_err();
+    assert_eq!(*err.kind(), ConfigError);
+}
+
+#[test]
+fn unloaded_config_section() {
+    let mut registry = new_registry();
+    let component = Box::<UnloadedComponent>::default() as Box<dyn Component<ExampleApp>>;
+    registry.register(vec![component]).unwrap();
+
+    let sections = Sections::load_toml("[unloaded]\nvalue = 1").unwrap();
+    registry.set_config_sections(sections).unwrap();
+
+    let err = registry
+        .after_config(&ExampleConfig::default())
+        .unwrap_err();
+    assert_eq!(*err.kind(), ComponentError);
+}
+
+#[test]
+fn dependency_graph() {
+    let mut registry = new_registry();
+    registry.register(init_components()).unwrap();
+
+    let graph = registry.dependency_graph();
+    assert_eq!(graph.nodes().len(), 3);
+    assert_eq!(graph.edges().len(), 2);
+
+    for edge in graph.edges() {
+        assert_eq!(edge.dependent, QUUX_COMPONENT_ID);
+    }
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph components {"));
+    assert!(dot.contains(r#""component::QuuxComponent" -> "component::FoobarComponent";"#));
+
+    let mermaid = graph.to_mermaid();
+    assert!(mermaid.starts_with("flowchart LR"));
+    assert_eq!(mermaid.matches(" --> ").count(), 2);
+}
+
+#[test]
+fn restart_component() {
+    let mut registry = new_registry();
+    registry.register(init_components()).unwrap();
+    registry.after_config(&ExampleConfig::default()).unwrap();
+
+    let handle = registry
+        .restart(
+            FOOBAR_COMPONENT_ID,
+            Shutdown::Graceful,
+            &ExampleConfig::default(),
+            || {
+                let mut foobar = FoobarComponent::default();
+                foobar.set_state("restarted foobar state");
+                Ok(Box::new(foobar))
+            },
+        )
+        .unwrap();
+
+    assert_eq!(handle.id(), FOOBAR_COMPONENT_ID);
+
+    // `QuuxComponent` should have been notified and re-injected
+    let quux_comp = registry.get_downcast_ref::<QuuxComponent>().unwrap();
+    assert_eq!(
+        quux_comp.foobar_state.as_ref().unwrap(),
+        "restarted foobar state"
+    );
+
+    let foobar_comp = registry.get_downcast_ref::<FoobarComponent>().unwrap();
+    assert_eq!(foobar_comp.state.as_ref().unwrap(), "hijacked!");
+}
+
+#[test]
+fn replace_component_mismatch() {
+    let mut registry = new_registry();
+    let degraded = DegradedComponent::default();
+    let shutdowns = Arc::clone(&degraded.shutdowns);
+    registry
+        .register(vec![Box::new(degraded) as Box<dyn Component<ExampleApp>>])
+        .unwrap();
+
+    let config = ExampleConfig::default();
+
+    let err = registry
+        .replace(
+            Box::<ImpostorComponent>::default(),
+            Shutdown::Graceful,
+            &config,
+        )
+        .unwrap_err();
+    assert_eq!(*err.kind(), ComponentError);
+    assert!(err.to_string().contains("mismatched type"), "{}", err);
+
+    let err = registry
+        .restart(DEGRADED_COMPONENT_ID, Shutdown::Graceful, &config, || {
+            Ok(Box::<BazComponent>::default())
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("mismatched ID"), "{}", err);
+
+    let err = registry
+        .restart(DEGRADED_COMPONENT_ID, Shutdown::Graceful, &config, || {
+            Err(ComponentError.context("construction failed").into())
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("construction failed"), "{}", err);
+
+    // The existing component is only shut down once its replacement is ready
+    assert_eq!(shutdowns.load(Ordering::SeqCst), 0);
+
+    registry
+        .replace(
+            Box::<DegradedComponent>::default(),
+            Shutdown::Graceful,
+            &config,
+        )
+        .unwrap();
+    assert_eq!(shutdowns.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn disabled_components() {
+    let mut registry = new_registry();
+    let sections =
+        Sections::load_toml("[components]\ndisabled = [\"component::BazComponent\"]").unwrap();
+    registry.set_config_sections(sections).unwrap();
+
+    let mut components = init_components();
+    components.retain(|component| component.id() != QUUX_COMPONENT_ID);
+    registry.register(components).unwrap();
+
+    assert_eq!(registry.len(), 1);
+    assert!(registry.get_by_id(FOOBAR_COMPONENT_ID).is_some());
+    assert!(registry.get_by_id(BAZ_COMPONENT_ID).is_none());
+}
+
+#[test]
+fn enabled_components() {
+    let mut registry = new_registry();
+    let sections =
+        Sections::load_toml("[components]\nenabled = [\"component::FoobarComponent\"]").unwrap();
+    registry.set_config_sections(sections).unwrap();
+
+    let mut components = init_components();
+    components.retain(|component| component.id() != QUUX_COMPONENT_ID);
+    registry.register(components).unwrap();
+
+    assert_eq!(registry.len(), 1);
+    assert!(registry.get_by_id(FOOBAR_COMPONENT_ID).is_some());
+}
+
+#[test]
+fn disabled_dependency() {
+    let mut registry = new_registry();
+    let sections =
+        Sections::load_toml("[components]\ndisabled = [\"component::FoobarComponent\"]").unwrap();
+    registry.set_config_sections(sections).unwrap();
+
+    let err = registry.register(init_components()).unwrap_err();
+    assert_eq!(*err.kind(), ComponentError);
+    assert!(err.to_string().contains("disabled component"));
+}
+
+#[test]
+fn unknown_component_filter() {
+    let mut registry = new_registry();
+    let sections =
+        Sections::load_toml("[components]\ndisabled = [\"component::Nonexistent\"]").unwrap();
+    registry.set_config_sections(sections).unwrap();
+
+    let err = registry.register(init_components()).unwrap_err();
+    assert_eq!(*err.kind(), ConfigError);
+}
+
+#[test]
+fn reserved_config_sections() {
+    /// Application configuration which rejects unknown sections
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct StrictConfig {
+        name: String,
+    }
+
+    let sections = Sections::load_toml(
+        "name = \"example\"\n[components]\ndisabled = []\n[tracing]\nlevel = \"debug\"",
+    )
+    .unwrap();
+    let config = sections.application_config::<StrictConfig>().unwrap();
+    assert_eq!(config.name, "example");
+}
+
+#[test]
+fn reserved_config_field() {
+    /// Application configuration with a field named after a reserved section
+    #[derive(Debug, Deserialize)]
+    struct CollidingConfig {
+        tracing: Option<String>,
+    }
+
+    // The field is left unset if the section isn't configured
+    let sections = Sections::load_toml("").unwrap();
+    let config = sections.application_config::<CollidingConfig>().unwrap();
+    assert!(config.tracing.is_none());
+
+    let sections = Sections::load_toml("[tracing]\nlevel = \"debug\"").unwrap();
+    let err = sections
+        .application_config::<CollidingConfig>()
+        .unwrap_err();
+    assert_eq!(*err.kind(), ConfigError);
+    assert!(err.to_string().contains("`[tracing]`"), "{}", err);
+}
+
+#[test]
+fn config_file_errors() {
+    let path = std::env::temp_dir().join(format!("abscissa-config-{}.toml", std::process::id()));
+    fs::write(&path, "name = \"example\"\ncount = \"many\"\n").unwrap();
+
+    /// Application configuration loaded from the file
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct FileConfig {
+        name: String,
+        count: u32,
+    }
+
+    let sections = Sections::load_toml_file(AbsPathBuf::canonicalize(&path).unwrap()).unwrap();
+    let err = sections.application_config::<FileConfig>().unwrap_err();
+    assert_eq!(*err.kind(), ConfigError);
+    assert!(
+        err.to_string().contains(&*path.to_string_lossy()),
+        "{}",
+        err
+    );
+
+    fs::write(&path, "name = ").unwrap();
+    let err = Sections::load_toml_file(AbsPathBuf::canonicalize(&path).unwrap()).unwrap_err();
+    assert!(
+        err.to_string().contains(&*path.to_string_lossy()),
+        "{}",
+        err
+    );
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn event_subscriptions() {
+    let mut registry = new_registry();
+    let cache = CacheComponent::default();
+    let flushes = Arc::clone(&cache.flushes);
+    registry
+        .register(vec![Box::new(cache) as Box<dyn Component<ExampleApp>>])
+        .unwrap();
+
+    let events = Arc::clone(registry.events());
+    assert_eq!(events.subscribers::<FlushCache>(), 1);
+    assert_eq!(events.publish(FlushCache), 1);
+    assert_eq!(flushes.load(Ordering::SeqCst), 1);
+
+    // Restarting the component replaces its subscriptions
+    let replacement = CacheComponent::default();
+    let replacement_flushes = Arc::clone(&replacement.flushes);
+    registry
+        .replace(
+            Box::new(replacement),
+            Shutdown::Graceful,
+            &ExampleConfig::default(),
+        )
+        .unwrap();
+
+    assert_eq!(events.subscribers::<FlushCache>(), 1);
+    events.publish(FlushCache);
+    assert_eq!(flushes.load(Ordering::SeqCst), 1);
+    assert_eq!(replacement_flushes.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn restart_failing_after_config() {
+    let mut registry = new_registry();
+    let cache = CacheComponent::default();
+    let flushes = Arc::clone(&cache.flushes);
+    registry
+        .register(vec![Box::new(cache) as Box<dyn Component<ExampleApp>>])
+        .unwrap();
+
+    let replacement = CacheComponent {
+        fail_config: true,
+        ..Default::default()
+    };
+    let replacement_flushes = Arc::clone(&replacement.flushes);
+
+    let err = registry
+        .replace(
+            Box::new(replacement),
+            Shutdown::Graceful,
+            &ExampleConfig::default(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("invalid cache configuration"),
+        "{}",
+        err
+    );
+
+    // The existing component remains registered and subscribed
+    assert!(
+        !registry
+            .get_downcast_ref::<CacheComponent>()
+            .unwrap()
+            .fail_config
+    );
+
+    let events = Arc::clone(registry.events());
+    assert_eq!(events.subscribers::<FlushCache>(), 1);
+    events.publish(FlushCache);
+    assert_eq!(flushes.load(Ordering::SeqCst), 1);
+    assert_eq!(replacement_flushes.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn typed_accessors() {
+    let mut registry = new_registry();
+    registry.register(init_components()).unwrap();
+
+    let foobar = registry.component::<FoobarComponent>().unwrap();
+    assert_eq!(foobar.state.as_ref().unwrap(), "original foobar state");
+
+    // Components are locked individually
+    let mut quux = registry.component_mut::<QuuxComponent>().unwrap();
+    quux.baz_initialized = true;
+    drop(quux);
+    drop(foobar);
+
+    let handle = registry.handle::<QuuxComponent>().unwrap();
+    assert_eq!(handle.id(), QUUX_COMPONENT_ID);
+    assert!(registry.resolve(handle).unwrap().baz_initialized);
+
+    let err = registry.component::<DatabaseComponent>().unwrap_err();
+    assert_eq!(*err.kind(), ComponentError);
+}
+
+#[test]
+fn borrowed_components() {
+    let mut registry = new_registry();
+    registry.register(init_components()).unwrap();
+    registry.after_config(&ExampleConfig::default()).unwrap();
+
+    let baz = registry.component::<BazComponent>().unwrap();
+    assert!(registry.get_mut_by_id(FOOBAR_COMPONENT_ID).is_some());
+    assert!(registry.get_downcast_ref::<BazComponent>().is_some());
+
+    // Mutable access to a borrowed component panics, like `RefCell`
+    let panic = panic::catch_unwind(AssertUnwindSafe(|| {
+        registry.get_downcast_mut::<BazComponent>();
+    }))
+    .unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "component already borrowed: component::BazComponent"
+    );
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| registry.iter_mut().count())).is_err());
+
+    // Restarting would lock `BazComponent`, so it fails instead of deadlocking
+    let err = registry
+        .restart(
+            FOOBAR_COMPONENT_ID,
+            Shutdown::Graceful,
+            &ExampleConfig::default(),
+            || Ok(Box::<FoobarComponent>::default()),
+        )
+        .unwrap_err();
+    assert_eq!(*err.kind(), ComponentError);
+    assert!(err.to_string().contains("borrowed"), "{}", err);
+
+    drop(baz);
+    assert!(registry.get_downcast_mut::<BazComponent>().is_some());
+
+    // Reports don't block on mutably borrowed components
+    let baz = registry.component_mut::<BazComponent>().unwrap();
+    assert_eq!(registry.dependency_graph().nodes().len(), registry.len());
+
+    let report = registry.health_report();
+    let entry = report
+        .iter()
+        .find(|entry| entry.id == BAZ_COMPONENT_ID)
+        .unwrap();
+    assert_eq!(entry.health, Health::Unknown("mutably borrowed".to_owned()));
+    assert!(matches!(report.health(), Health::Unknown(_)));
+
+    assert!(registry.get_by_id(FOOBAR_COMPONENT_ID).is_some());
+    assert!(
+        panic::catch_unwind(AssertUnwindSafe(|| registry.get_by_id(BAZ_COMPONENT_ID))).is_err()
+    );
+
+    drop(baz);
+    assert!(registry.health_report().health().is_healthy());
+}
+
+#[test]
+fn lifecycle_timings() {
+    let mut registry = new_registry();
+    let baz = registry.construct(|| Ok(BazComponent::default())).unwrap();
+
+    let mut components = init_components();
+    components[1] = baz;
+    registry.register(components).unwrap();
+    registry.after_config(&ExampleConfig::default()).unwrap();
+
+    let report = registry.timings();
+    let phases = report
+        .component(QUUX_COMPONENT_ID)
+        .iter()
+        .map(|timing| timing.phase)
+        .collect::<Vec<_>>();
+
+    assert!(phases.contains(&Phase::AfterConfig));
+    assert!(phases.contains(&Phase::RegisterDependency(FOOBAR_COMPONENT_ID)));
+    assert!(phases.contains(&Phase::RegisterDependency(BAZ_COMPONENT_ID)));
+
+    let baz_phases = report.component(BAZ_COMPONENT_ID);
+    assert_eq!(baz_phases.iter().next().unwrap().phase, Phase::Construct);
+    assert!(report.shutdown().is_empty());
+    assert!(
+        report
+            .to_string()
+            .contains("register_dependency(component::FoobarComponent)")
+    );
+}
+
+#[test]
+fn lifecycle_timings_capped() {
+    let registry = new_registry();
+
+    for _ in 0..=timing::MAX_TIMINGS {
+        registry.construct(|| Ok(BazComponent::default())).unwrap();
+    }
+
+    assert_eq!(registry.timings().iter().count(), timing::MAX_TIMINGS);
+}
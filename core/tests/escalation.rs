@@ -0,0 +1,173 @@
+//! Tests for crashing applications when a managed thread's failure escalates
+//!
+//! Each test re-runs this test binary to boot an application whose command
+//! blocks until it's shut down, as crashing exits the process.
+
+use abscissa_core::{
+    Application, Command, Configurable, FrameworkError, Runnable, StandardPaths, application,
+    application::AppCell,
+    clap::Parser,
+    config,
+    thread::{Name, Policy},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    io::Read,
+    path::PathBuf,
+    process::{self, Stdio},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Environment variable selecting how the child process's thread fails
+const MODE_ENV_VAR: &str = "ABSCISSA_ESCALATION_TEST_MODE";
+
+/// Amount of time the child process has to crash
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+static APP: AppCell<BlockingApp> = AppCell::new();
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BlockingConfig {}
+
+/// Spawn a thread which fails, then block until the application shuts down
+#[derive(Command, Debug, Parser)]
+pub struct BlockingCommand {
+    /// Either `escalate` or `critical`
+    mode: String,
+}
+
+impl Configurable<BlockingConfig> for BlockingCommand {
+    fn config_path(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+impl Runnable for BlockingCommand {
+    fn run(&self) {
+        let mut threads = APP.state().threads_mut();
+
+        match self.mode.as_str() {
+            "escalate" => threads
+                .spawn_supervised(
+                    "escalating".parse::<Name>().unwrap(),
+                    Policy::Escalate,
+                    || {
+                        thread::sleep(Duration::from_millis(100));
+                        panic!("boom");
+                    },
+                )
+                .unwrap(),
+            "critical" => {
+                threads
+                    .spawn_critical(
+                        "critical".parse::<Name>().unwrap(),
+                        || -> Result<(), String> {
+                            thread::sleep(Duration::from_millis(100));
+                            Err("boom".to_owned())
+                        },
+                    )
+                    .unwrap();
+            }
+            other => panic!("unknown mode: {}", other),
+        }
+
+        drop(threads);
+
+        loop {
+            thread::park();
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BlockingApp {
+    config: Option<Arc<BlockingConfig>>,
+    state: application::State<Self>,
+}
+
+impl Application for BlockingApp {
+    type Cmd = BlockingCommand;
+    type Cfg = BlockingConfig;
+    type Paths = StandardPaths;
+
+    fn config(&self) -> config::Reader<BlockingConfig> {
+        Arc::clone(self.config.as_ref().expect("config not loaded"))
+    }
+
+    fn state(&self) -> &application::State<Self> {
+        &self.state
+    }
+
+    fn register_components(&mut self, command: &Self::Cmd) -> Result<(), FrameworkError> {
+        let framework_components = self.framework_components(command)?;
+        let mut app_components = self.state.components_mut();
+        app_components.register(framework_components)
+    }
+
+    fn after_config(&mut self, config: Self::Cfg) -> Result<(), FrameworkError> {
+        let mut components = self.state.components_mut();
+        components.after_config(&config)?;
+        self.config = Some(Arc::new(config));
+        Ok(())
+    }
+}
+
+/// Boots the application when run as a child process by the tests below
+#[test]
+fn child() {
+    if let Ok(mode) = env::var(MODE_ENV_VAR) {
+        BlockingApp::run(&APP, ["blocking", mode.as_str()]);
+    }
+}
+
+/// Run the application in a child process, returning its stderr once it exits
+fn crash(mode: &str) -> String {
+    let mut child = process::Command::new(env::current_exe().unwrap())
+        .args(["child", "--exact", "--nocapture"])
+        .env(MODE_ENV_VAR, mode)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let deadline = Instant::now() + TIMEOUT;
+
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            child.kill().unwrap();
+            panic!("application didn't crash within {:?}", TIMEOUT);
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+
+    assert_eq!(status.code(), Some(1), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn escalated_failure_crashes_blocking_command() {
+    let stderr = crash("escalate");
+    assert!(stderr.contains("thread 'escalating' failed"), "{}", stderr);
+}
+
+#[test]
+fn critical_failure_crashes_blocking_command() {
+    let stderr = crash("critical");
+    assert!(stderr.contains("thread 'critical' failed"), "{}", stderr);
+}
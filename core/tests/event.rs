@@ -0,0 +1,59 @@
+//! Tests for Abscissa's event bus
+
+use abscissa_core::{event::Bus, thread};
+use std::{
+    sync::{Arc, mpsc},
+    time::Duration,
+};
+
+/// Example event
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ping(u32);
+
+#[test]
+fn unsubscribe() {
+    let bus = Bus::default();
+    let subscription = bus.subscribe(|_: &Ping| ());
+    assert_eq!(bus.publish(Ping(1)), 1);
+
+    assert!(bus.unsubscribe(subscription));
+    assert!(!bus.unsubscribe(subscription));
+    assert_eq!(bus.publish(Ping(2)), 0);
+}
+
+#[test]
+fn channel_subscription() {
+    let bus = Bus::default();
+    let (_, receiver) = bus.channel::<Ping>();
+
+    bus.publish(Ping(1));
+    assert_eq!(receiver.recv().unwrap(), Ping(1));
+
+    // Dropping the receiver removes the subscription
+    drop(receiver);
+    assert_eq!(bus.publish(Ping(2)), 0);
+    assert_eq!(bus.subscribers::<Ping>(), 0);
+}
+
+#[test]
+fn thread_subscriber() {
+    let bus = Arc::new(Bus::default());
+    let mut threads = thread::Manager::default();
+    let (sender, receiver) = mpsc::channel();
+
+    let name = "ping-subscriber".parse::<thread::Name>().unwrap();
+
+    bus.spawn_subscriber(&mut threads, name, move |ping: Ping| {
+        sender.send(ping).unwrap();
+    })
+    .unwrap();
+
+    bus.publish(Ping(42));
+
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+        Ping(42)
+    );
+
+    threads.join().unwrap();
+}
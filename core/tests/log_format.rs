@@ -0,0 +1,119 @@
+//! Tests for Abscissa's log output formats
+//!
+//! These live in their own test binary as they install the global tracing
+//! subscriber.
+
+mod example_app;
+
+use self::example_app::ExampleApp;
+use abscissa_core::{
+    Component, component, fs,
+    terminal::ColorChoice,
+    trace::{self, Format, Tracing, file, sink},
+};
+use serde_json::Value;
+use std::{env, process, sync::Arc};
+
+/// Component whose lifecycle events are logged
+#[derive(Component, Debug, Default)]
+pub struct LoggedComponent {}
+
+#[test]
+fn log_formats() {
+    let dir = env::temp_dir().join(format!("abscissa-log-formats-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let formats = [Format::Full, Format::Compact, Format::Pretty, Format::Json];
+
+    let config = formats.iter().fold(
+        trace::Config {
+            stdout: false,
+            timestamps: false,
+            ..trace::Config::from("debug".to_owned())
+        },
+        |config, format| {
+            let path = dir.join(format!("{}.log", format));
+            let output = sink::Output::File(file::Config::new(path));
+            config.with_sink(
+                format.to_string(),
+                sink::Config::new(output).with_format(*format),
+            )
+        },
+    );
+
+    let tracing = Tracing::new(config, ColorChoice::Never).unwrap();
+
+    let registry = component::Registry::<ExampleApp>::new(Arc::default());
+    registry
+        .construct(|| Ok(LoggedComponent::default()))
+        .unwrap();
+
+    tracing::info_span!("request", request_id = 42).in_scope(|| tracing::warn!("access denied"));
+    tracing.flush().unwrap();
+
+    let read = |format: Format| fs::read_to_string(dir.join(format!("{}.log", format))).unwrap();
+    let event = |log: &str| {
+        log.lines()
+            .find(|line| line.contains("access denied"))
+            .unwrap()
+            .to_owned()
+    };
+
+    let full = read(Format::Full);
+    assert!(
+        event(&full).contains("request{request_id=42}: log_format: access denied"),
+        "{}",
+        full
+    );
+    assert!(
+        full.contains("component{phase=construct component=log_format::LoggedComponent}"),
+        "{}",
+        full
+    );
+
+    let compact = read(Format::Compact);
+    assert!(
+        event(&compact).contains("request: log_format: access denied request_id=42"),
+        "{}",
+        compact
+    );
+
+    let pretty = read(Format::Pretty);
+    assert!(pretty.contains("log_format: access denied"), "{}", pretty);
+    assert!(
+        pretty.contains("::request with request_id: 42"),
+        "{}",
+        pretty
+    );
+
+    let json = read(Format::Json)
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let request = json
+        .iter()
+        .find(|event| event["fields"]["message"] == "access denied")
+        .unwrap();
+    assert_eq!(request["level"], "WARN");
+    assert_eq!(request["span"]["name"], "request");
+    assert_eq!(request["span"]["request_id"], 42);
+    assert!(request["threadName"].is_string());
+
+    // Component lifecycle events carry the component's ID
+    let construct = json
+        .iter()
+        .find(|event| event["span"]["phase"] == "construct")
+        .unwrap();
+    assert_eq!(construct["span"]["name"], "component");
+    assert_eq!(
+        construct["span"]["component"],
+        "log_format::LoggedComponent"
+    );
+    assert_eq!(
+        construct["spans"][0]["component"],
+        construct["span"]["component"]
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,173 @@
+//! Tests for Abscissa's scheduler component
+
+use abscissa_core::{
+    scheduler::{Job, ManualClock, Overlap, Schedule, Scheduler},
+    thread::Manager,
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// 2024-01-01T00:00:00Z
+const START_TIME: u64 = 1_704_067_200;
+
+/// Interval used by the tests below
+const INTERVAL: Duration = Duration::from_secs(30);
+
+/// Get the time the given number of seconds after `START_TIME`
+fn time(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(START_TIME + secs)
+}
+
+/// Wait until the given condition holds (or panic after a timeout)
+fn wait_until(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Create a job which counts the number of times it has run
+fn counting_job(schedule: Schedule) -> (Job, Arc<AtomicU32>) {
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&runs);
+
+    let job = Job::new("counter", schedule, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    (job, runs)
+}
+
+#[test]
+fn interval_jobs() {
+    let clock = ManualClock::new(time(0));
+    let mut scheduler = Scheduler::with_clock(clock.clone());
+    let mut threads = Manager::default();
+    let (job, runs) = counting_job(Schedule::every(INTERVAL));
+
+    scheduler.schedule(&mut threads, job).unwrap();
+    assert_eq!(scheduler.jobs().collect::<Vec<_>>(), ["counter"]);
+
+    for n in 1..=3 {
+        wait_until(|| clock.deadlines() == [time(30 * u64::from(n))]);
+        clock.advance(INTERVAL);
+        wait_until(|| runs.load(Ordering::SeqCst) == n);
+    }
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn cron_jobs() {
+    let clock = ManualClock::new(time(0));
+    let mut scheduler = Scheduler::with_clock(clock.clone());
+    let mut threads = Manager::default();
+    let (job, runs) = counting_job(Schedule::cron("0 2 * * *").unwrap());
+
+    scheduler.schedule(&mut threads, job).unwrap();
+
+    wait_until(|| clock.deadlines() == [time(2 * 3600)]);
+    clock.set(time(2 * 3600));
+    wait_until(|| runs.load(Ordering::SeqCst) == 1);
+    wait_until(|| clock.deadlines() == [time(26 * 3600)]);
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn overlap_skip() {
+    let clock = ManualClock::new(time(0));
+    let mut scheduler = Scheduler::with_clock(clock.clone());
+    let mut threads = Manager::default();
+
+    // Takes 70 seconds to run, missing the next two scheduled runs
+    let job_clock = clock.clone();
+    let job = Job::new("slow", Schedule::every(INTERVAL), move || {
+        job_clock.advance(Duration::from_secs(70));
+    });
+
+    scheduler.schedule(&mut threads, job).unwrap();
+
+    wait_until(|| clock.deadlines() == [time(30)]);
+    clock.advance(INTERVAL);
+    wait_until(|| clock.deadlines() == [time(130)]);
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn overlap_queue() {
+    let clock = ManualClock::new(time(0));
+    let mut scheduler = Scheduler::with_clock(clock.clone());
+    let mut threads = Manager::default();
+    let runs = Arc::new(AtomicU32::new(0));
+
+    // The first run takes 70 seconds, missing the next two scheduled runs
+    let job_clock = clock.clone();
+    let counter = Arc::clone(&runs);
+    let job = Job::new("slow", Schedule::every(INTERVAL), move || {
+        if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+            job_clock.advance(Duration::from_secs(70));
+        }
+    })
+    .with_overlap(Overlap::Queue);
+
+    scheduler.schedule(&mut threads, job).unwrap();
+
+    wait_until(|| clock.deadlines() == [time(30)]);
+    clock.advance(INTERVAL);
+
+    // Runs again immediately (once), then resumes the schedule
+    wait_until(|| runs.load(Ordering::SeqCst) == 2);
+    wait_until(|| clock.deadlines() == [time(130)]);
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn jitter() {
+    let clock = ManualClock::new(time(0));
+    let mut scheduler = Scheduler::with_clock(clock.clone());
+    let mut threads = Manager::default();
+    let (job, _) = counting_job(Schedule::every(INTERVAL));
+
+    scheduler
+        .schedule(&mut threads, job.with_jitter(Duration::from_secs(10)))
+        .unwrap();
+
+    wait_until(|| !clock.deadlines().is_empty());
+    let deadline = clock.deadlines()[0];
+    assert!(deadline >= time(30) && deadline <= time(40));
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn stops_on_termination() {
+    let mut scheduler = Scheduler::new();
+    let mut threads = Manager::default();
+    let (job, runs) = counting_job(Schedule::every(Duration::from_secs(3600)));
+
+    scheduler.schedule(&mut threads, job).unwrap();
+
+    let started_at = Instant::now();
+    threads.join().unwrap();
+    assert!(started_at.elapsed() < Duration::from_secs(10));
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn zero_interval() {
+    let mut scheduler = Scheduler::new();
+    let mut threads = Manager::default();
+    let (job, _) = counting_job(Schedule::every(Duration::ZERO));
+
+    assert!(scheduler.schedule(&mut threads, job).is_err());
+}
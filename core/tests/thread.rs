@@ -0,0 +1,478 @@
+//! Tests for Abscissa's thread manager
+
+use abscissa_core::{
+    FrameworkErrorKind::ThreadError,
+    Shutdown, crossbeam_channel,
+    thread::{self, Manager, Name, Policy, Pool, pool},
+};
+use std::{
+    convert::Infallible,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Wait until the given condition holds (or panic after a timeout)
+fn wait_until(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn panic_message_on_join() {
+    let mut threads = Manager::default();
+    let name = "panicky".parse::<Name>().unwrap();
+    threads.spawn(name.clone(), || panic!("boom")).unwrap();
+
+    wait_until(|| threads.status(&name).unwrap().last_failure.is_some());
+    let status = threads.status(&name).unwrap();
+    assert_eq!(status.restarts, 0);
+    assert_eq!(status.last_failure.unwrap().message, "boom");
+
+    let err = threads.join().unwrap_err();
+    assert_eq!(*err.kind(), ThreadError);
+    assert!(err.to_string().contains("thread 'panicky' panicked: boom"));
+}
+
+#[test]
+fn join_reports_all_failures() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel();
+
+    threads
+        .spawn("first".parse::<Name>().unwrap(), || panic!("one"))
+        .unwrap();
+    threads
+        .spawn("second".parse::<Name>().unwrap(), || panic!("two"))
+        .unwrap();
+    threads
+        .spawn("healthy".parse::<Name>().unwrap(), move || {
+            thread::wait_for_termination(Duration::from_secs(60));
+            sender.send(()).unwrap();
+        })
+        .unwrap();
+
+    let err = threads.join().unwrap_err();
+    assert!(err.to_string().contains("thread 'first' panicked: one"));
+    assert!(err.to_string().contains("thread 'second' panicked: two"));
+
+    // Threads after the failed ones are still joined
+    receiver.try_recv().unwrap();
+    assert!(threads.list().is_empty());
+}
+
+#[test]
+fn backoff_restarts() {
+    let mut threads = Manager::default();
+    let name = "flaky".parse::<Name>().unwrap();
+    let attempts = Arc::new(AtomicU32::new(0));
+    let policy = Policy::backoff(Duration::from_millis(1), Duration::from_millis(10));
+
+    let thread_attempts = Arc::clone(&attempts);
+    threads
+        .spawn_supervised(name.clone(), policy, move || {
+            let attempt = thread_attempts.fetch_add(1, Ordering::SeqCst);
+
+            if attempt < 2 {
+                panic!("attempt {} failed", attempt);
+            }
+        })
+        .unwrap();
+
+    wait_until(|| attempts.load(Ordering::SeqCst) == 3);
+    let status = threads.status(&name).unwrap();
+    assert_eq!(status.restarts, 2);
+    assert_eq!(status.last_failure.unwrap().message, "attempt 1 failed");
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn backoff_max_restarts() {
+    let mut threads = Manager::default();
+    let name = "doomed".parse::<Name>().unwrap();
+    let policy = Policy::Backoff {
+        initial: Duration::from_millis(1),
+        max: Duration::from_millis(1),
+        max_restarts: Some(1),
+    };
+
+    threads
+        .spawn_supervised(name.clone(), policy, || panic!("doomed"))
+        .unwrap();
+
+    wait_until(|| threads.status(&name).unwrap().restarts == 1);
+    assert!(threads.join().is_err());
+}
+
+#[test]
+fn escalation() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel();
+
+    threads.set_escalation_handler(move |name, failure| {
+        sender
+            .send((name.to_string(), failure.message.clone()))
+            .unwrap();
+    });
+
+    threads
+        .spawn_supervised(
+            "critical".parse::<Name>().unwrap(),
+            Policy::Escalate,
+            || panic!("critical failure"),
+        )
+        .unwrap();
+
+    let (name, message) = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(name, "critical");
+    assert_eq!(message, "critical failure");
+    assert!(threads.join().is_err());
+}
+
+#[test]
+fn default_escalation() {
+    let mut threads = Manager::default();
+    assert!(threads.escalated().is_none());
+
+    threads
+        .spawn_supervised(
+            "critical".parse::<Name>().unwrap(),
+            Policy::Escalate,
+            || panic!("critical failure"),
+        )
+        .unwrap();
+
+    // Failures are queued for the thread shutting down the application
+    // rather than terminating the process
+    assert!(threads.join().is_err());
+
+    let (name, failure) = threads.escalated().unwrap();
+    assert_eq!(name.to_string(), "critical");
+    assert_eq!(failure.message, "critical failure");
+    assert!(threads.escalated().is_none());
+}
+
+#[test]
+fn wait_for_termination() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel();
+
+    threads
+        .spawn("waiter".parse::<Name>().unwrap(), move || {
+            let terminated = thread::wait_for_termination(Duration::from_secs(60));
+            sender.send(terminated).unwrap();
+        })
+        .unwrap();
+
+    let started_at = Instant::now();
+    threads.join().unwrap();
+    assert!(started_at.elapsed() < Duration::from_secs(10));
+    assert!(receiver.recv().unwrap());
+}
+
+#[test]
+fn sleep_or_terminate() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel();
+
+    threads
+        .spawn("sleeper".parse::<Name>().unwrap(), move || {
+            // Sleeps run to completion until termination is requested
+            assert!(thread::sleep_or_terminate(Duration::from_millis(1)));
+            sender.send(()).unwrap();
+
+            while thread::sleep_or_terminate(Duration::from_secs(60)) {}
+        })
+        .unwrap();
+
+    receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    let started_at = Instant::now();
+    threads.join().unwrap();
+    assert!(started_at.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn termination_receiver() {
+    let mut threads = Manager::default();
+    let (_work_sender, work_receiver) = crossbeam_channel::unbounded::<()>();
+
+    threads
+        .spawn("selector".parse::<Name>().unwrap(), move || {
+            let terminate = thread::termination_receiver();
+
+            crossbeam_channel::select! {
+                recv(work_receiver) -> _ => panic!("unexpected work"),
+                recv(terminate) -> message => assert!(message.is_err()),
+            }
+        })
+        .unwrap();
+
+    threads.join().unwrap();
+}
+
+#[test]
+fn join_timeout_stuck_thread() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel::<()>();
+
+    threads
+        .spawn("prompt".parse::<Name>().unwrap(), || {
+            thread::wait_for_termination(Duration::from_secs(60));
+        })
+        .unwrap();
+
+    // Ignores its kill switch, exiting only once the sender is dropped
+    threads
+        .spawn("stuck".parse::<Name>().unwrap(), move || {
+            let _ = receiver.recv();
+        })
+        .unwrap();
+
+    let stuck = threads.join_timeout(Duration::from_millis(100)).unwrap();
+    assert_eq!(stuck, ["stuck".parse::<Name>().unwrap()]);
+    assert!(threads.status(&"prompt".parse().unwrap()).is_none());
+
+    drop(sender);
+    assert!(
+        threads
+            .join_timeout(Duration::from_secs(10))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn result_values() {
+    let mut threads = Manager::default();
+    let handle = threads
+        .spawn_result("answer".parse::<Name>().unwrap(), || {
+            Ok::<_, Infallible>(42)
+        })
+        .unwrap();
+
+    assert_eq!(handle.name().as_ref(), "answer");
+    assert_eq!(threads.wait(handle).unwrap(), 42);
+    threads.join().unwrap();
+}
+
+#[test]
+fn result_errors() {
+    let mut threads = Manager::default();
+    let name = "failing".parse::<Name>().unwrap();
+    threads
+        .spawn_result::<(), _, _>(name.clone(), || Err("no route to host"))
+        .unwrap();
+
+    wait_until(|| threads.status(&name).unwrap().last_failure.is_some());
+    let status = threads.status(&name).unwrap();
+    assert_eq!(status.last_failure.unwrap().message, "no route to host");
+
+    let err = threads.join().unwrap_err();
+    assert_eq!(*err.kind(), ThreadError);
+    assert!(
+        err.to_string()
+            .contains("thread 'failing' failed: no route to host")
+    );
+}
+
+#[test]
+fn critical_thread_errors() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel();
+
+    threads.set_escalation_handler(move |name, failure| {
+        sender
+            .send((name.to_string(), failure.message.clone()))
+            .unwrap();
+    });
+
+    let handle = threads
+        .spawn_critical("critical".parse::<Name>().unwrap(), || {
+            Err::<(), _>("disk full")
+        })
+        .unwrap();
+
+    let (name, message) = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(name, "critical");
+    assert_eq!(message, "disk full");
+
+    let err = threads.wait(handle).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("thread 'critical' failed: disk full")
+    );
+}
+
+#[test]
+fn pool_graceful_shutdown() {
+    let mut threads = Manager::default();
+    let config = pool::Config::new(2);
+    let pool = Pool::spawn(&mut threads, "pool".parse::<Name>().unwrap(), &config).unwrap();
+
+    let worker_names = pool
+        .workers()
+        .iter()
+        .map(Name::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(worker_names, ["pool/0", "pool/1"]);
+
+    let completed = Arc::new(AtomicU32::new(0));
+
+    for _ in 0..10 {
+        let completed = Arc::clone(&completed);
+        pool.execute(move || {
+            completed.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    }
+
+    pool.shutdown(&mut threads, Shutdown::Graceful).unwrap();
+    assert_eq!(completed.load(Ordering::SeqCst), 10);
+    assert!(pool.execute(|| ()).is_err());
+    threads.join().unwrap();
+}
+
+#[test]
+fn pool_forced_shutdown() {
+    let mut threads = Manager::default();
+    let config = pool::Config {
+        workers: 1,
+        queue_capacity: 1,
+    };
+    let pool = Pool::spawn(&mut threads, "pool".parse::<Name>().unwrap(), &config).unwrap();
+    let (sender, receiver) = mpsc::channel();
+
+    // Occupy the only worker until termination is requested
+    pool.execute(move || {
+        sender.send(()).unwrap();
+        thread::wait_for_termination(Duration::from_secs(60));
+    })
+    .unwrap();
+
+    receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    let completed = Arc::new(AtomicU32::new(0));
+    let queued = Arc::clone(&completed);
+    pool.execute(move || {
+        queued.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+    assert_eq!(pool.queued(), 1);
+
+    let err = pool.try_execute(|| ()).unwrap_err();
+    assert!(err.to_string().contains("pool 'pool' queue is full"));
+
+    pool.shutdown(&mut threads, Shutdown::Forced).unwrap();
+    assert_eq!(completed.load(Ordering::SeqCst), 0);
+    threads.join().unwrap();
+}
+
+#[test]
+fn pool_panicking_jobs() {
+    let mut threads = Manager::default();
+    let pool = Pool::spawn(
+        &mut threads,
+        "pool".parse::<Name>().unwrap(),
+        &pool::Config::new(1),
+    )
+    .unwrap();
+
+    let completed = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&completed);
+    pool.execute(|| panic!("bad job")).unwrap();
+    pool.execute(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+
+    // The worker survives the panic and runs the next job
+    pool.shutdown(&mut threads, Shutdown::Graceful).unwrap();
+    assert_eq!(pool.panicked(), 1);
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn pool_invalid_config() {
+    let mut threads = Manager::default();
+
+    for config in [
+        pool::Config::new(0),
+        pool::Config {
+            workers: 1,
+            queue_capacity: 0,
+        },
+    ] {
+        let err = Pool::spawn(&mut threads, "pool".parse::<Name>().unwrap(), &config).unwrap_err();
+        assert_eq!(*err.kind(), ThreadError);
+    }
+
+    assert!(threads.list().is_empty());
+}
+
+#[test]
+fn thread_info() {
+    let mut threads = Manager::default();
+    let (sender, receiver) = mpsc::channel::<()>();
+    let started = SystemTime::now();
+
+    // Ignores its kill switch, exiting only once the sender is dropped
+    threads
+        .spawn("worker".parse::<Name>().unwrap(), move || {
+            let _ = receiver.recv();
+        })
+        .unwrap();
+
+    threads
+        .spawn("panicky".parse::<Name>().unwrap(), || panic!("boom"))
+        .unwrap();
+
+    threads
+        .spawn_result::<(), _, _>("failing".parse::<Name>().unwrap(), || Err("failed"))
+        .unwrap();
+
+    threads
+        .spawn("done".parse::<Name>().unwrap(), || ())
+        .unwrap();
+
+    let state = |name: &str| threads.info(&name.parse().unwrap()).unwrap().state;
+    wait_until(|| {
+        ["panicky", "failing", "done"]
+            .iter()
+            .all(|name| state(name).is_exited())
+    });
+
+    assert_eq!(state("worker"), thread::State::Running);
+    assert_eq!(state("panicky"), thread::State::Panicked);
+    assert_eq!(state("failing"), thread::State::Failed);
+    assert_eq!(state("done"), thread::State::Exited);
+
+    let list = threads.list();
+    let names = list
+        .iter()
+        .map(|info| info.name.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["done", "failing", "panicky", "worker"]);
+    assert!(
+        list.iter()
+            .all(|info| info.started >= started && info.restarts == 0)
+    );
+
+    let worker = "worker".parse::<Name>().unwrap();
+    assert!(threads.request_termination(&worker));
+    assert_eq!(
+        threads.info(&worker).unwrap().state,
+        thread::State::Terminating
+    );
+
+    drop(sender);
+    assert!(threads.join().is_err());
+}
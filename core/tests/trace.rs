@@ -0,0 +1,325 @@
+//! Tests for Abscissa's tracing subsystem
+
+use abscissa_core::{
+    assert_logged, assert_not_logged,
+    config::Sections,
+    fs,
+    terminal::ColorChoice,
+    testing,
+    trace::{self, Format, RollingFile, Rotation, SpanEvents, Tracing, file, sink},
+};
+use flate2::read::GzDecoder;
+use std::{
+    env,
+    io::{Read, Write},
+    path::PathBuf,
+    process,
+};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Create an empty temporary directory for the given test
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("abscissa-{}-{}", test_name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Write a line to the given log file
+fn write_line(log: &RollingFile, line: &str) {
+    writeln!(log.make_writer(), "{}", line).unwrap();
+}
+
+#[test]
+fn size_rotation() {
+    let dir = temp_dir("size-rotation");
+    let mut config = file::Config::new(dir.join("logs").join("app.log"));
+    config.rotation = Rotation::Size(16);
+    config.retention = 2;
+
+    let log = RollingFile::open(config).unwrap();
+
+    for n in 1..=4 {
+        write_line(&log, &format!("line number {}", n));
+    }
+
+    log.flush().unwrap();
+
+    let read = |name: &str| fs::read_to_string(dir.join("logs").join(name)).unwrap();
+    assert_eq!(read("app.log"), "line number 4\n");
+    assert_eq!(read("app.log.1"), "line number 3\n");
+    assert_eq!(read("app.log.2"), "line number 2\n");
+    assert!(!dir.join("logs").join("app.log.3").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compressed_rotation() {
+    let dir = temp_dir("compressed-rotation");
+    let mut config = file::Config::new(dir.join("app.log"));
+    config.compress = true;
+
+    let log = RollingFile::open(config).unwrap();
+    write_line(&log, "before rotation");
+    log.rotate().unwrap();
+    write_line(&log, "after rotation");
+    log.flush().unwrap();
+
+    let mut rotated = String::new();
+    GzDecoder::new(fs::File::open(dir.join("app.log.1.gz")).unwrap())
+        .read_to_string(&mut rotated)
+        .unwrap();
+
+    assert_eq!(rotated, "before rotation\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("app.log")).unwrap(),
+        "after rotation\n"
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn appends_to_existing_file() {
+    let dir = temp_dir("existing-file");
+    let path = dir.join("app.log");
+
+    for line in ["first run", "second run"] {
+        let log = RollingFile::open(file::Config::new(&path)).unwrap();
+        write_line(&log, line);
+    }
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "first run\nsecond run\n"
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn config_section() {
+    let sections = Sections::load_toml(
+        r#"
+        [tracing]
+        level = "info"
+        format = "json"
+        timestamps = false
+        span_events = "close"
+
+        [tracing.directives]
+        hyper = "warn"
+        "myapp::db" = "debug"
+
+        [tracing.file]
+        path = "/var/log/myapp.log"
+        rotation = { size = 1048576 }
+
+        [tracing.sinks.terminal]
+        output = "stderr"
+        level = "warn"
+        "#,
+    )
+    .unwrap();
+
+    let config: trace::Config = sections.get("tracing").unwrap().clone().try_into().unwrap();
+    assert_eq!(config.filter(), "info,hyper=warn,myapp::db=debug");
+    assert_eq!(config.format, Format::Json);
+    assert!(config.stdout);
+    assert!(!config.timestamps);
+    assert_eq!(config.span_events, SpanEvents::Close);
+
+    assert_eq!(
+        config.sinks["terminal"],
+        sink::Config::new(sink::Output::Stderr).with_level("warn")
+    );
+    assert_eq!(
+        config.all_sinks().keys().collect::<Vec<_>>(),
+        ["file", "stdout", "terminal"]
+    );
+
+    let file = config.file.unwrap();
+    assert_eq!(file.rotation, Rotation::Size(1_048_576));
+    assert_eq!(file.retention, file::DEFAULT_RETENTION);
+    assert!(!file.compress);
+}
+
+#[test]
+fn independent_sink_filters() {
+    let dir = temp_dir("sinks");
+    let sink = |name: &str| sink::Output::File(file::Config::new(dir.join(name)));
+
+    let config = trace::Config {
+        stdout: false,
+        ..trace::Config::from("info".to_owned())
+    }
+    .with_sink(
+        "debug",
+        sink::Config::new(sink("debug.log")).with_level("debug"),
+    )
+    .with_sink(
+        "warnings",
+        sink::Config::new(sink("warnings.json"))
+            .with_level("warn")
+            .with_format(Format::Json),
+    )
+    .with_sink("default", sink::Config::new(sink("default.log")));
+
+    let mut tracing = Tracing::new(config, ColorChoice::Never).unwrap();
+    assert_eq!(tracing.sinks(), ["debug", "default", "warnings"]);
+
+    tracing::debug!("first debug event");
+    tracing::warn!("first warning");
+
+    tracing.reload_sink_filter("warnings", "off").unwrap();
+    tracing.reload_filter("debug").unwrap();
+    assert_eq!(tracing.sink_filter("warnings").unwrap(), "off");
+    assert!(tracing.reload_sink_filter("nonexistent", "debug").is_err());
+
+    tracing::debug!("second debug event");
+    tracing::warn!("second warning");
+
+    let span = tracing::info_span!("request", user = tracing::field::Empty);
+    span.record("user", "alice");
+    span.in_scope(|| tracing::warn!("recorded field"));
+    tracing.flush().unwrap();
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+
+    let debug = read("debug.log");
+    assert!(debug.contains("first debug event") && debug.contains("second debug event"));
+
+    let warnings = read("warnings.json");
+    assert_eq!(warnings.lines().count(), 1);
+    assert!(warnings.starts_with('{') && warnings.contains("first warning"));
+
+    let default = read("default.log");
+    assert!(!default.contains("first debug event"));
+    assert!(default.contains("first warning") && default.contains("second debug event"));
+
+    // Fields recorded after a span is created are only formatted once, even
+    // though the sinks share the same field formatter
+    for log in [&debug, &default] {
+        assert!(log.contains("request{user=\"alice\"}: "), "{}", log);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+/// Bind a Unix datagram socket standing in for a log daemon
+#[cfg(unix)]
+fn bind_socket(dir: &std::path::Path) -> (PathBuf, std::os::unix::net::UnixDatagram) {
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join("socket");
+    let socket = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+    (path, socket)
+}
+
+/// Log a warning inside of a span using the given layer
+#[cfg(unix)]
+fn log_warning(layer: impl tracing_subscriber::Layer<tracing_subscriber::Registry> + Send + Sync) {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let subscriber = tracing_subscriber::Registry::default().with(layer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("request", request_id = 42);
+        let _entered = span.enter();
+        tracing::warn!(user = "alice", "access \"denied\"");
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn syslog_sink() {
+    let dir = temp_dir("syslog");
+    let (path, socket) = bind_socket(&dir);
+
+    let config = trace::syslog::Config {
+        transport: trace::syslog::Transport::Unix(path),
+        facility: trace::syslog::Facility::Daemon,
+        app_name: Some("myapp".to_owned()),
+        hostname: Some("myhost".to_owned()),
+        ..Default::default()
+    };
+
+    log_warning(trace::Syslog::new(config).unwrap());
+
+    let mut buf = [0u8; 1024];
+    let len = socket.recv(&mut buf).unwrap();
+    let msg = std::str::from_utf8(&buf[..len]).unwrap();
+
+    // Facility 3 (daemon) * 8 + severity 4 (warning)
+    assert!(msg.starts_with("<28>1 "), "{}", msg);
+    assert!(msg.contains(&format!(" myhost myapp {} - ", process::id())));
+    assert!(msg.ends_with(
+        "[tracing@32473 target=\"trace\" request_id=\"42\" user=\"alice\"] \u{feff}access \"denied\""
+    ));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn journald_sink() {
+    let dir = temp_dir("journald");
+    let (path, socket) = bind_socket(&dir);
+
+    let config = trace::journald::Config {
+        socket: path,
+        syslog_identifier: Some("myapp".to_owned()),
+    };
+
+    log_warning(trace::Journald::new(config).unwrap());
+
+    let mut buf = [0u8; 1024];
+    let len = socket.recv(&mut buf).unwrap();
+    let entry = std::str::from_utf8(&buf[..len]).unwrap();
+    let fields = entry.lines().collect::<Vec<_>>();
+
+    for field in [
+        "MESSAGE=access \"denied\"",
+        "PRIORITY=4",
+        "SYSLOG_IDENTIFIER=myapp",
+        "TARGET=trace",
+        "REQUEST_ID=42",
+        "USER=alice",
+    ] {
+        assert!(fields.contains(&field), "missing {}: {:?}", field, fields);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn capture_events() {
+    let capture = testing::trace::capture();
+
+    std::thread::spawn(|| tracing::warn!("logged by another thread"))
+        .join()
+        .unwrap();
+
+    let span = tracing::info_span!("request");
+    span.in_scope(|| tracing::warn!(attempt = 3, "retrying {}", "request"));
+    tracing::debug!("details");
+
+    assert_logged!(tracing::Level::WARN, "^retrying request$");
+    assert_logged!(tracing::Level::DEBUG, "{}", "details");
+    assert_not_logged!(tracing::Level::WARN, "another thread");
+
+    let events = capture.matching(tracing::Level::WARN, "retrying");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].target, "trace");
+    assert_eq!(events[0].fields["attempt"], "3");
+
+    capture.clear();
+    assert!(capture.events().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "no WARN event matching")]
+fn capture_missing_event() {
+    let _capture = testing::trace::capture();
+    tracing::info!("all good");
+    assert_logged!(tracing::Level::WARN, "something bad");
+}
@@ -0,0 +1,79 @@
+//! Tests for command-line overrides of the `[tracing]` configuration section
+//!
+//! These live in their own test binary as they install the global tracing
+//! subscriber.
+
+use abscissa_core::{
+    command::Verbosity,
+    component::ConfigSection,
+    config::Sections,
+    fs,
+    terminal::ColorChoice,
+    trace::{self, Format, Overrides, Tracing},
+};
+use std::{env, process};
+
+#[test]
+fn config_section_with_verbose_flag() {
+    let dir = env::temp_dir().join(format!("abscissa-trace-overrides-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("app.log");
+
+    // `-v --log-format json`
+    let overrides = Overrides {
+        format: Some(Format::Json),
+        ..Verbosity {
+            verbose: 1,
+            quiet: 0,
+        }
+        .tracing_overrides()
+    };
+
+    let config = trace::Config {
+        stdout: false,
+        ..trace::Config::default()
+    };
+
+    let mut tracing =
+        Tracing::with_overrides(overrides.apply(config), overrides, ColorChoice::Never).unwrap();
+    assert_eq!(tracing.filter(), "debug");
+
+    // The section doesn't set a level and asks for a different format
+    let sections = Sections::load_toml(format!(
+        r#"
+        [tracing]
+        stdout = false
+        format = "full"
+        timestamps = false
+
+        [tracing.directives]
+        hyper = "warn"
+
+        [tracing.sinks.app]
+        output = {{ file = {{ path = {:?} }} }}
+        "#,
+        path
+    ))
+    .unwrap();
+
+    let section: trace::Config = sections.get("tracing").unwrap().clone().try_into().unwrap();
+    tracing.after_config_section(section).unwrap();
+    assert_eq!(tracing.sinks(), ["app"]);
+    assert_eq!(tracing.filter(), "debug,hyper=warn");
+
+    tracing::debug!("debug event");
+    tracing.flush().unwrap();
+
+    let log = fs::read_to_string(&path).unwrap();
+    let line = log
+        .lines()
+        .find(|line| line.contains("debug event"))
+        .unwrap();
+    assert!(
+        line.starts_with('{') && line.contains(r#""level":"DEBUG""#),
+        "{}",
+        log
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
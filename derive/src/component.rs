@@ -36,6 +36,7 @@ fn derive_injectable_inner(s: &Structure<'_>) -> TokenStream {
     let name = &s.ast().ident;
     let abscissa_core = attrs.abscissa_core_crate();
     let dependency_methods = attrs.dependency_methods();
+    let config_methods = attrs.config_methods();
 
     s.gen_impl(quote! {
         #[allow(unknown_lints)]
@@ -56,6 +57,8 @@ fn derive_injectable_inner(s: &Structure<'_>) -> TokenStream {
             }
 
             #dependency_methods
+
+            #config_methods
         }
     })
 }
@@ -71,6 +74,9 @@ struct ComponentAttributes {
 
     /// Dependent components to inject into the current component
     inject: Vec<InjectAttribute>,
+
+    /// Name of the configuration section belonging to the current component
+    config: Option<String>,
 }
 
 impl ComponentAttributes {
@@ -78,6 +84,7 @@ impl ComponentAttributes {
     pub fn from_derive_input(input: &DeriveInput) -> Self {
         let mut core = false;
         let mut inject = Vec::new();
+        let mut config = None;
 
         for attr in &input.attrs {
             if !attr.path().is_ident("component") {
@@ -91,6 +98,9 @@ impl ComponentAttributes {
                 } else if nested.path.is_ident("inject") {
                     inject.push(InjectAttribute::from_nested_meta(&nested)?);
                     Ok(())
+                } else if nested.path.is_ident("config") {
+                    config = Some(parse_str_value(&nested, "config")?);
+                    Ok(())
                 } else {
                     Err(nested.error("malformed `component` attribute"))
                 }
@@ -98,7 +108,11 @@ impl ComponentAttributes {
             .expect("error parsing meta");
         }
 
-        Self { core, inject }
+        Self {
+            core,
+            inject,
+            config,
+        }
     }
 
     /// Ident for the `abscissa_core` crate.
@@ -143,6 +157,44 @@ impl ComponentAttributes {
             }
         }
     }
+
+    /// Generate `Injectable::config_section()` and `load_config_section()`
+    pub fn config_methods(&self) -> TokenStream {
+        let Some(name) = &self.config else {
+            return quote!();
+        };
+
+        let abscissa_core = self.abscissa_core_crate();
+
+        quote! {
+            fn config_section(&self) -> Option<&'static str> {
+                Some(#name)
+            }
+
+            fn load_config_section(
+                &mut self,
+                section: &#abscissa_core::config::Section,
+            ) -> Result<(), #abscissa_core::FrameworkError> {
+                #abscissa_core::component::section::load(self, #name, section)
+            }
+        }
+    }
+}
+
+/// Parse the string literal value of a `#[component(...)]` attribute field.
+fn parse_str_value(meta: &ParseNestedMeta<'_>, name: &str) -> Result<String, syn::Error> {
+    let Ok(value) = meta.value() else {
+        return Err(meta.error(format!("expected value for `{}` attribute", name)));
+    };
+
+    let Ok(lit) = value.parse::<Lit>() else {
+        return Err(value.error(format!("expected literal for `{}` value", name)));
+    };
+
+    match lit {
+        Lit::Str(lit_str) => Ok(lit_str.value()),
+        _ => Err(value.error(format!("expected string literal for `{}` value", name))),
+    }
 }
 
 /// Attribute declaring a dependency which should be injected
@@ -152,18 +204,7 @@ pub struct InjectAttribute(String);
 impl InjectAttribute {
     /// Parse an [`InjectAttribute`] from [`NestedMeta`].
     pub fn from_nested_meta(meta: &ParseNestedMeta<'_>) -> Result<Self, syn::Error> {
-        let Ok(value) = meta.value() else {
-            return Err(meta.error("expected value for `inject` attribute"));
-        };
-
-        let Ok(lit) = value.parse::<Lit>() else {
-            return Err(value.error("expected literal for `inject` value"));
-        };
-
-        match lit {
-            Lit::Str(lit_str) => Ok(Self(lit_str.value())),
-            _ => Err(value.error("expected string literal for `inject` value")),
-        }
+        parse_str_value(meta, "inject").map(Self)
     }
 
     /// Parse the callback and component ID of the value of an inject attribute.
@@ -157,15 +157,7 @@ fn take_runtime<A>(app: &'static AppCell<A>) -> Result<(Runtime, Option<Duration
 where
     A: Application,
 {
-    let mut components = app.state().components_mut();
-    let component = components
-        .get_downcast_mut::<TokioComponent>()
-        .ok_or_else(|| {
-            FrameworkError::from(format_err!(
-                FrameworkErrorKind::ComponentError,
-                "TokioComponent not registered"
-            ))
-        })?;
+    let mut component = app.state().component_mut::<TokioComponent>()?;
     component
         .runtime
         .take()